@@ -0,0 +1,99 @@
+// Times `CommunityDetector::from_csv` against the original loader, which fed
+// records through `par_bridge` into a graph behind a single mutex.
+//
+//     cargo run --release --example ingest_benchmark [users] [interactions]
+use community_detection::{CommunityDetector, generate_interaction_csv_seeded};
+use csv::Reader;
+use petgraph::Graph;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const RUNS: usize = 3;
+
+// The loader as it was before the parallel ingest pipeline
+fn mutex_loader(filename: &str) -> Result<Graph<String, u32>, csv::Error> {
+    let graph = Arc::new(Mutex::new(Graph::new()));
+    let node_indices = Arc::new(Mutex::new(HashMap::new()));
+
+    Reader::from_path(filename)?
+        .into_records()
+        .par_bridge()
+        .for_each(|record| {
+            let record = record.unwrap();
+            let user1 = record[0].to_string();
+            let user2 = record[1].to_string();
+            let weight: u32 = record[2].parse().unwrap_or(1);
+
+            let mut graph = graph.lock().unwrap();
+            let mut node_indices = node_indices.lock().unwrap();
+
+            let node1 = *node_indices
+                .entry(user1.clone())
+                .or_insert_with(|| graph.add_node(user1));
+            let node2 = *node_indices
+                .entry(user2.clone())
+                .or_insert_with(|| graph.add_node(user2));
+
+            if let Some(edge) = graph.find_edge(node1, node2) {
+                graph[edge] += weight;
+            } else {
+                graph.add_edge(node1, node2, weight);
+            }
+        });
+
+    Ok(Arc::try_unwrap(graph).unwrap().into_inner().unwrap())
+}
+
+// Fastest of `RUNS` runs, and the graph from the last one
+fn best_of<T>(mut load: impl FnMut() -> T) -> (Duration, T) {
+    let mut best = Duration::MAX;
+    let mut result = None;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        result = Some(load());
+        best = best.min(start.elapsed());
+    }
+    (best, result.unwrap())
+}
+
+fn total_weight(graph: &Graph<String, u32>) -> u64 {
+    graph.edge_weights().map(|&weight| weight as u64).sum()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let users: usize = args.next().map_or(Ok(100_000), |arg| arg.parse())?;
+    let interactions: usize = args.next().map_or(Ok(5_000_000), |arg| arg.parse())?;
+
+    let path = std::env::temp_dir().join(format!("ingest-benchmark-{}.csv", std::process::id()));
+    let filename = path.to_str().unwrap();
+    generate_interaction_csv_seeded(users, interactions, filename, 1)?;
+    let size = std::fs::metadata(&path)?.len() as f64 / (1024.0 * 1024.0);
+    println!(
+        "{} interactions between {} users ({:.1} MiB), {} threads, best of {}",
+        interactions,
+        users,
+        size,
+        rayon::current_num_threads(),
+        RUNS
+    );
+
+    let (mutex_time, old) = best_of(|| mutex_loader(filename));
+    let (parallel_time, new) = best_of(|| CommunityDetector::from_csv(filename));
+    std::fs::remove_file(&path)?;
+    let (old, new) = (old?, new?.graph);
+
+    assert_eq!(old.node_count(), new.node_count());
+    assert_eq!(old.edge_count(), new.edge_count());
+    assert_eq!(total_weight(&old), total_weight(&new));
+
+    println!("mutex loader:    {:>8.3}s", mutex_time.as_secs_f64());
+    println!("parallel loader: {:>8.3}s", parallel_time.as_secs_f64());
+    println!(
+        "speedup:         {:>8.1}x",
+        mutex_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use crate::error::{RecordError, RecordErrorKind};
+use crate::weight::EdgeWeight;
+
+// How the weights of repeated `source,target` pairs are combined into the
+// weight of a single edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Aggregation {
+    #[default]
+    Sum,
+    Max,
+    Min,
+    // Arithmetic mean, rounded for integer weights
+    Mean,
+    // Number of occurrences, ignoring the weights
+    Count,
+    // Weight of the occurrence that comes last in the input
+    Last,
+}
+
+// What happens when an aggregated weight does not fit the weight type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    // Clamp to the largest representable weight
+    #[default]
+    Saturate,
+    // Fail the load with `RecordErrorKind::WeightOverflow`
+    Error,
+}
+
+// Running aggregate of all occurrences of one pair. Accumulators from
+// different chunks can be merged, as long as they are merged in input order.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Accumulator<W> {
+    weight: W,
+    count: u64,
+    sum: f64,
+    // Line of the latest occurrence
+    line: u64,
+}
+
+impl<W: EdgeWeight> Accumulator<W> {
+    pub fn new(weight: W, line: u64) -> Self {
+        Accumulator {
+            weight,
+            count: 1,
+            sum: weight.to_f64(),
+            line,
+        }
+    }
+
+    // Start from the finished weight of an edge that was in the graph before
+    // the current load
+    pub fn existing(weight: W, aggregation: Aggregation) -> Self {
+        let count = match aggregation {
+            Aggregation::Count => weight.to_f64() as u64,
+            _ => 1,
+        };
+        Accumulator {
+            weight,
+            count,
+            sum: weight.to_f64(),
+            line: 0,
+        }
+    }
+
+    // Fold in `other`, which covers occurrences later in the input.
+    pub fn merge(
+        &mut self,
+        other: &Self,
+        aggregation: Aggregation,
+        overflow: OverflowPolicy,
+    ) -> Result<(), RecordError> {
+        match aggregation {
+            Aggregation::Sum => {
+                self.weight = match self.weight.checked_add(other.weight) {
+                    Some(weight) => weight,
+                    None => overflowed(overflow, other.line)?,
+                };
+            }
+            Aggregation::Max => {
+                if other.weight > self.weight {
+                    self.weight = other.weight;
+                }
+            }
+            Aggregation::Min => {
+                if other.weight < self.weight {
+                    self.weight = other.weight;
+                }
+            }
+            Aggregation::Last => self.weight = other.weight,
+            Aggregation::Mean | Aggregation::Count => {}
+        }
+
+        self.count = self.count.saturating_add(other.count);
+        self.sum += other.sum;
+        self.line = other.line;
+        Ok(())
+    }
+
+    pub fn finish(
+        &self,
+        aggregation: Aggregation,
+        overflow: OverflowPolicy,
+    ) -> Result<W, RecordError> {
+        match aggregation {
+            Aggregation::Count => match W::from_f64(self.count as f64) {
+                Some(weight) => Ok(weight),
+                None => overflowed(overflow, self.line),
+            },
+            Aggregation::Mean => Ok(W::from_f64(self.sum / self.count as f64).unwrap_or(W::MAX)),
+            _ => Ok(self.weight),
+        }
+    }
+}
+
+fn overflowed<W: EdgeWeight>(overflow: OverflowPolicy, line: u64) -> Result<W, RecordError> {
+    match overflow {
+        OverflowPolicy::Saturate => Ok(W::MAX),
+        OverflowPolicy::Error => Err(RecordError::new(line, RecordErrorKind::WeightOverflow)),
+    }
+}
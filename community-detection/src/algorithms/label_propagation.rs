@@ -0,0 +1,98 @@
+use super::{CommunityAlgorithm, Partition, WeightedAdjacency, membership_to_partition};
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+
+// Weighted label propagation (Raghavan, Albert & Kumara, 2007). Each node
+// repeatedly adopts the label with the largest summed incident weight among
+// its neighbours. Runs in near-linear time per iteration.
+#[derive(Clone, Debug)]
+pub struct LabelPropagation {
+    pub seed: u64,
+    pub max_iterations: usize,
+}
+
+impl Default for LabelPropagation {
+    fn default() -> Self {
+        LabelPropagation {
+            seed: 0,
+            max_iterations: 100,
+        }
+    }
+}
+
+impl<Ty: EdgeType, W: EdgeWeight> CommunityAlgorithm<Ty, W> for LabelPropagation {
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition {
+        let adjacency = WeightedAdjacency::from_graph(graph);
+        membership_to_partition(&self.run(&adjacency))
+    }
+}
+
+impl LabelPropagation {
+    pub fn new(seed: u64, max_iterations: usize) -> Self {
+        LabelPropagation {
+            seed,
+            max_iterations,
+        }
+    }
+
+    pub(crate) fn run(&self, adjacency: &WeightedAdjacency) -> Vec<usize> {
+        let node_count = adjacency.len();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut labels: Vec<usize> = (0..node_count).collect();
+        let mut order: Vec<usize> = (0..node_count).collect();
+
+        let mut weights = vec![0.0; node_count];
+        let mut seen = vec![false; node_count];
+        let mut touched = Vec::new();
+        let mut best = Vec::new();
+
+        for _ in 0..self.max_iterations {
+            order.shuffle(&mut rng);
+            let mut changes = 0;
+
+            for &node in &order {
+                for &(other, weight) in &adjacency.neighbors[node] {
+                    let label = labels[other];
+                    if !seen[label] {
+                        seen[label] = true;
+                        touched.push(label);
+                    }
+                    weights[label] += weight;
+                }
+
+                let max_weight = touched
+                    .iter()
+                    .map(|&label| weights[label])
+                    .fold(f64::NEG_INFINITY, f64::max);
+                best.extend(
+                    touched
+                        .iter()
+                        .copied()
+                        .filter(|&label| weights[label] == max_weight),
+                );
+
+                // Keep the current label on ties so the process can settle.
+                if !best.is_empty() && !best.contains(&labels[node]) {
+                    best.sort_unstable();
+                    labels[node] = *best.choose(&mut rng).unwrap();
+                    changes += 1;
+                }
+
+                for label in touched.drain(..) {
+                    weights[label] = 0.0;
+                    seen[label] = false;
+                }
+                best.clear();
+            }
+
+            if changes == 0 {
+                break;
+            }
+        }
+
+        labels
+    }
+}
@@ -0,0 +1,266 @@
+use super::louvain::local_moving;
+use super::{
+    CommunityAlgorithm, Partition, WeightedAdjacency, membership_to_partition, renumber,
+    split_disconnected,
+};
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+
+// Leiden algorithm (Traag, Waltman & van Eck, 2019). Like Louvain, but every
+// community is refined into well-connected subcommunities before aggregation,
+// so no returned community is internally disconnected.
+#[derive(Clone, Debug)]
+pub struct Leiden {
+    pub resolution: f64,
+}
+
+impl Default for Leiden {
+    fn default() -> Self {
+        Leiden { resolution: 1.0 }
+    }
+}
+
+impl<Ty: EdgeType, W: EdgeWeight> CommunityAlgorithm<Ty, W> for Leiden {
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition {
+        let adjacency = WeightedAdjacency::from_graph(graph);
+        membership_to_partition(&self.run(&adjacency))
+    }
+}
+
+impl Leiden {
+    pub fn new(resolution: f64) -> Self {
+        Leiden { resolution }
+    }
+
+    pub(crate) fn run(&self, adjacency: &WeightedAdjacency) -> Vec<usize> {
+        // Original node -> node of the current aggregate graph
+        let mut node_to_aggregate: Vec<usize> = (0..adjacency.len()).collect();
+        let mut membership: Vec<usize> = (0..adjacency.len()).collect();
+        let mut current = adjacency.clone();
+
+        loop {
+            local_moving(&current, self.resolution, &mut membership);
+            let (partition, community_count) = renumber(&membership);
+            membership = partition;
+            if community_count == current.len() {
+                break;
+            }
+
+            let (refined, refined_count) =
+                renumber(&refine(&current, &membership, self.resolution));
+            if refined_count == current.len() {
+                // Nothing could be merged, so aggregating would not make progress.
+                break;
+            }
+
+            // Aggregate on the refined partition, but start the next level
+            // from the unrefined one.
+            let mut next_membership = vec![0; refined_count];
+            for (node, &subcommunity) in refined.iter().enumerate() {
+                next_membership[subcommunity] = membership[node];
+            }
+            for aggregate in node_to_aggregate.iter_mut() {
+                *aggregate = refined[*aggregate];
+            }
+            current = current.aggregate(&refined, refined_count);
+            membership = next_membership;
+        }
+
+        let membership: Vec<usize> = node_to_aggregate
+            .iter()
+            .map(|&aggregate| membership[aggregate])
+            .collect();
+        // Refinement already keeps communities connected; this only matters
+        // if the loop above stopped early.
+        split_disconnected(adjacency, &membership)
+    }
+}
+
+// Merge nodes into subcommunities inside each community of `membership`.
+// Only singletons that are well connected to their community are moved, and
+// only into subcommunities they are linked to, so every subcommunity stays
+// connected.
+fn refine(adjacency: &WeightedAdjacency, membership: &[usize], resolution: f64) -> Vec<usize> {
+    let node_count = adjacency.len();
+    let total_weight = adjacency.total_weight;
+    let mut refined: Vec<usize> = (0..node_count).collect();
+    if total_weight <= 0.0 {
+        return refined;
+    }
+
+    let mut community_degree = vec![0.0; node_count];
+    for (node, &community) in membership.iter().enumerate() {
+        community_degree[community] += adjacency.degrees[node];
+    }
+
+    // Weight from each node to the rest of its community
+    let internal: Vec<f64> = adjacency
+        .neighbors
+        .iter()
+        .enumerate()
+        .map(|(node, links)| {
+            links
+                .iter()
+                .filter(|&&(other, _)| membership[other] == membership[node])
+                .map(|&(_, weight)| weight)
+                .sum()
+        })
+        .collect();
+
+    let mut subcommunity_degree = adjacency.degrees.clone();
+    let mut subcommunity_size = vec![1usize; node_count];
+    // Weight from each subcommunity to the rest of its community
+    let mut cut = internal.clone();
+
+    let mut links = vec![0.0; node_count];
+    let mut seen = vec![false; node_count];
+    let mut touched = Vec::new();
+
+    for node in 0..node_count {
+        if subcommunity_size[refined[node]] > 1 {
+            continue;
+        }
+
+        let degree = adjacency.degrees[node];
+        let community_total = community_degree[membership[node]];
+        if internal[node] < resolution * degree * (community_total - degree) / total_weight {
+            continue;
+        }
+
+        for &(other, weight) in &adjacency.neighbors[node] {
+            if membership[other] != membership[node] {
+                continue;
+            }
+            let subcommunity = refined[other];
+            if !seen[subcommunity] {
+                seen[subcommunity] = true;
+                touched.push(subcommunity);
+            }
+            links[subcommunity] += weight;
+        }
+
+        let own = refined[node];
+        let mut best = own;
+        let mut best_gain = 0.0;
+        for &subcommunity in &touched {
+            if subcommunity == own {
+                continue;
+            }
+            let sub_degree = subcommunity_degree[subcommunity];
+            let well_connected = cut[subcommunity]
+                >= resolution * sub_degree * (community_total - sub_degree) / total_weight;
+            let gain = links[subcommunity] - resolution * degree * sub_degree / total_weight;
+            if well_connected && gain >= best_gain {
+                best = subcommunity;
+                best_gain = gain;
+            }
+        }
+
+        if best != own {
+            refined[node] = best;
+            subcommunity_size[own] = 0;
+            subcommunity_size[best] += 1;
+            subcommunity_degree[own] = 0.0;
+            subcommunity_degree[best] += degree;
+            cut[best] += internal[node] - 2.0 * links[best];
+        }
+
+        for subcommunity in touched.drain(..) {
+            links[subcommunity] = 0.0;
+            seen[subcommunity] = false;
+        }
+    }
+
+    refined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    // Six groups of 15 nodes, densely linked inside and sparsely across
+    fn planted(seed: u64) -> WeightedAdjacency {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut edges = Vec::new();
+        for a in 0..90 {
+            for b in a + 1..90 {
+                let probability = if a / 15 == b / 15 { 0.4 } else { 0.02 };
+                if rng.gen_bool(probability) {
+                    edges.push((a, b, rng.gen_range(1..=5) as f64));
+                }
+            }
+        }
+        WeightedAdjacency::from_edges(90, edges.into_iter())
+    }
+
+    fn connected(adjacency: &WeightedAdjacency, members: &[usize]) -> bool {
+        let mut inside = vec![false; adjacency.len()];
+        for &node in members {
+            inside[node] = true;
+        }
+        let mut visited = vec![false; adjacency.len()];
+        visited[members[0]] = true;
+        let mut stack = vec![members[0]];
+        let mut reached = 1;
+        while let Some(node) = stack.pop() {
+            for &(other, _) in &adjacency.neighbors[node] {
+                if inside[other] && !visited[other] {
+                    visited[other] = true;
+                    reached += 1;
+                    stack.push(other);
+                }
+            }
+        }
+        reached == members.len()
+    }
+
+    // Every subcommunity lies within one community and is connected, and
+    // returns how many subcommunities there are
+    fn check_refinement(adjacency: &WeightedAdjacency, membership: &[usize]) -> usize {
+        let refined = refine(adjacency, membership, 1.0);
+        let subcommunities = membership_to_partition(&renumber(&refined).0);
+        for members in &subcommunities {
+            let nodes: Vec<usize> = members.iter().map(|node| node.index()).collect();
+            assert!(
+                nodes
+                    .iter()
+                    .all(|&node| membership[node] == membership[nodes[0]]),
+                "{:?} spans communities",
+                nodes
+            );
+            assert!(connected(adjacency, &nodes), "{:?} is disconnected", nodes);
+        }
+        subcommunities.len()
+    }
+
+    #[test]
+    fn refinement_merges_within_communities() {
+        for seed in 0..5 {
+            let adjacency = planted(seed);
+            let mut membership: Vec<usize> = (0..adjacency.len()).collect();
+            local_moving(&adjacency, 1.0, &mut membership);
+            let count = check_refinement(&adjacency, &membership);
+            assert!(count < adjacency.len() / 2, "seed {}: {}", seed, count);
+        }
+    }
+
+    #[test]
+    fn refinement_splits_disconnected_communities() {
+        // Communities that mix nodes from every group are internally sparse
+        // and may be disconnected; refinement must not join separate parts.
+        for seed in 0..5 {
+            let adjacency = planted(seed);
+            let membership: Vec<usize> = (0..adjacency.len()).map(|node| node % 4).collect();
+            check_refinement(&adjacency, &membership);
+        }
+
+        // Two triangles put in one community are never joined, while each
+        // triangle on its own community merges whole.
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3)];
+        let adjacency = WeightedAdjacency::from_edges(6, edges.iter().map(|&(a, b)| (a, b, 1.0)));
+        assert!(check_refinement(&adjacency, &[0; 6]) >= 2);
+        assert_eq!(check_refinement(&adjacency, &[0, 0, 0, 1, 1, 1]), 2);
+    }
+}
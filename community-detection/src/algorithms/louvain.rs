@@ -0,0 +1,127 @@
+use super::{CommunityAlgorithm, Partition, WeightedAdjacency, membership_to_partition, renumber};
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+
+// Minimum modularity gain for a move to count, to avoid looping on rounding noise.
+const MIN_GAIN: f64 = 1e-12;
+
+// Multi-level modularity optimization (Blondel et al., 2008).
+#[derive(Clone, Debug)]
+pub struct Louvain {
+    pub resolution: f64,
+}
+
+impl Default for Louvain {
+    fn default() -> Self {
+        Louvain { resolution: 1.0 }
+    }
+}
+
+impl<Ty: EdgeType, W: EdgeWeight> CommunityAlgorithm<Ty, W> for Louvain {
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition {
+        let adjacency = WeightedAdjacency::from_graph(graph);
+        membership_to_partition(&self.run(adjacency))
+    }
+}
+
+impl Louvain {
+    pub fn new(resolution: f64) -> Self {
+        Louvain { resolution }
+    }
+
+    // Returns the community of every node of `adjacency`.
+    pub(crate) fn run(&self, adjacency: WeightedAdjacency) -> Vec<usize> {
+        let mut node_to_community: Vec<usize> = (0..adjacency.len()).collect();
+        let mut current = adjacency;
+
+        loop {
+            let mut membership: Vec<usize> = (0..current.len()).collect();
+            if !local_moving(&current, self.resolution, &mut membership) {
+                break;
+            }
+
+            let (membership, community_count) = renumber(&membership);
+            for community in node_to_community.iter_mut() {
+                *community = membership[*community];
+            }
+            current = current.aggregate(&membership, community_count);
+        }
+
+        node_to_community
+    }
+}
+
+// Greedily move single nodes to the neighbouring community with the best
+// modularity gain until no move improves it. Returns whether anything moved.
+pub(crate) fn local_moving(
+    adjacency: &WeightedAdjacency,
+    resolution: f64,
+    membership: &mut [usize],
+) -> bool {
+    let total_weight = adjacency.total_weight;
+    if total_weight <= 0.0 {
+        return false;
+    }
+
+    let node_count = adjacency.len();
+    let mut community_degree = vec![0.0; node_count];
+    for (node, &community) in membership.iter().enumerate() {
+        community_degree[community] += adjacency.degrees[node];
+    }
+
+    let mut links = vec![0.0; node_count];
+    let mut seen = vec![false; node_count];
+    let mut touched = Vec::new();
+    let mut improved = false;
+
+    loop {
+        let mut moves = 0;
+
+        for node in 0..node_count {
+            let degree = adjacency.degrees[node];
+            let current = membership[node];
+
+            for &(other, weight) in &adjacency.neighbors[node] {
+                let community = membership[other];
+                if !seen[community] {
+                    seen[community] = true;
+                    touched.push(community);
+                }
+                links[community] += weight;
+            }
+
+            community_degree[current] -= degree;
+            let gain = |community: usize| {
+                links[community] - resolution * community_degree[community] * degree / total_weight
+            };
+
+            let mut best = current;
+            let mut best_gain = gain(current);
+            for &community in &touched {
+                let candidate = gain(community);
+                if candidate > best_gain + MIN_GAIN {
+                    best = community;
+                    best_gain = candidate;
+                }
+            }
+
+            community_degree[best] += degree;
+            if best != current {
+                membership[node] = best;
+                moves += 1;
+            }
+
+            for community in touched.drain(..) {
+                links[community] = 0.0;
+                seen[community] = false;
+            }
+        }
+
+        if moves == 0 {
+            break;
+        }
+        improved = true;
+    }
+
+    improved
+}
@@ -0,0 +1,181 @@
+use crate::weight::EdgeWeight;
+use petgraph::algo::tarjan_scc;
+use petgraph::graph::NodeIndex;
+use petgraph::{Directed, EdgeType, Graph};
+use std::collections::HashMap;
+
+pub mod label_propagation;
+pub mod leiden;
+pub mod louvain;
+
+pub use label_propagation::LabelPropagation;
+pub use leiden::Leiden;
+pub use louvain::Louvain;
+
+// A partition of the graph: every inner vector holds the nodes of one community.
+pub type Partition = Vec<Vec<NodeIndex>>;
+
+// Anything that can split an interaction graph into communities. Implement
+// this to run your own algorithm through `CommunityDetector::detect_communities_with`.
+// `Ty` is the edge type of the graph, directed unless the detector was
+// loaded in undirected mode, and `W` the edge weight type.
+pub trait CommunityAlgorithm<Ty: EdgeType = Directed, W: EdgeWeight = u32> {
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition;
+}
+
+impl<F, Ty: EdgeType, W: EdgeWeight> CommunityAlgorithm<Ty, W> for F
+where
+    F: Fn(&Graph<String, W, Ty>) -> Partition,
+{
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition {
+        self(graph)
+    }
+}
+
+// Strongly connected components, the original behaviour of `detect_communities`.
+// On undirected graphs these are the connected components.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StronglyConnected;
+
+impl<Ty: EdgeType, W: EdgeWeight> CommunityAlgorithm<Ty, W> for StronglyConnected {
+    fn detect(&self, graph: &Graph<String, W, Ty>) -> Partition {
+        tarjan_scc(graph)
+    }
+}
+
+// Symmetric weighted adjacency used by the modularity-based algorithms.
+// Edge direction is ignored and parallel edges are merged, so `A -> B` and
+// `B -> A` contribute to a single undirected edge.
+#[derive(Clone, Debug)]
+pub(crate) struct WeightedAdjacency {
+    pub neighbors: Vec<Vec<(usize, f64)>>,
+    pub self_loops: Vec<f64>,
+    pub degrees: Vec<f64>,
+    pub total_weight: f64,
+}
+
+impl WeightedAdjacency {
+    pub fn from_graph<W: EdgeWeight, Ty: EdgeType>(graph: &Graph<String, W, Ty>) -> Self {
+        let edges = graph.raw_edges().iter().map(|edge| {
+            (
+                edge.source().index(),
+                edge.target().index(),
+                edge.weight.to_f64(),
+            )
+        });
+        Self::from_edges(graph.node_count(), edges)
+    }
+
+    pub fn from_edges(node_count: usize, edges: impl Iterator<Item = (usize, usize, f64)>) -> Self {
+        let mut merged: Vec<HashMap<usize, f64>> = vec![HashMap::new(); node_count];
+        let mut self_loops = vec![0.0; node_count];
+
+        for (a, b, weight) in edges {
+            if a == b {
+                self_loops[a] += weight;
+            } else {
+                *merged[a].entry(b).or_insert(0.0) += weight;
+                *merged[b].entry(a).or_insert(0.0) += weight;
+            }
+        }
+
+        let neighbors: Vec<Vec<(usize, f64)>> = merged
+            .into_iter()
+            .map(|links| {
+                let mut links: Vec<_> = links.into_iter().collect();
+                links.sort_unstable_by_key(|&(node, _)| node);
+                links
+            })
+            .collect();
+
+        // A self-loop counts twice towards the degree, as in the usual
+        // definition of modularity.
+        let degrees: Vec<f64> = neighbors
+            .iter()
+            .zip(&self_loops)
+            .map(|(links, self_loop)| links.iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * self_loop)
+            .collect();
+        let total_weight = degrees.iter().sum();
+
+        WeightedAdjacency {
+            neighbors,
+            self_loops,
+            degrees,
+            total_weight,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.neighbors.len()
+    }
+
+    // Collapse every community of `membership` into a single node. Edges
+    // inside a community become self-loops of the aggregated node.
+    pub fn aggregate(&self, membership: &[usize], community_count: usize) -> Self {
+        let internal = self
+            .self_loops
+            .iter()
+            .enumerate()
+            .map(|(node, &weight)| (membership[node], membership[node], weight));
+        let links = self.neighbors.iter().enumerate().flat_map(|(node, links)| {
+            links
+                .iter()
+                .filter(move |&&(other, _)| node < other)
+                .map(move |&(other, weight)| (membership[node], membership[other], weight))
+        });
+        Self::from_edges(community_count, internal.chain(links))
+    }
+}
+
+// Renumber arbitrary community ids to `0..count`, in order of first appearance.
+pub(crate) fn renumber(membership: &[usize]) -> (Vec<usize>, usize) {
+    let mut mapping = HashMap::new();
+    let renumbered = membership
+        .iter()
+        .map(|&community| {
+            let next = mapping.len();
+            *mapping.entry(community).or_insert(next)
+        })
+        .collect();
+    (renumbered, mapping.len())
+}
+
+// Split every community into its connected components.
+pub(crate) fn split_disconnected(
+    adjacency: &WeightedAdjacency,
+    membership: &[usize],
+) -> Vec<usize> {
+    let mut component = vec![usize::MAX; adjacency.len()];
+    let mut next = 0;
+    let mut stack = Vec::new();
+
+    for start in 0..adjacency.len() {
+        if component[start] != usize::MAX {
+            continue;
+        }
+        component[start] = next;
+        stack.push(start);
+        while let Some(node) = stack.pop() {
+            for &(other, _) in &adjacency.neighbors[node] {
+                if component[other] == usize::MAX && membership[other] == membership[node] {
+                    component[other] = next;
+                    stack.push(other);
+                }
+            }
+        }
+        next += 1;
+    }
+
+    component
+}
+
+// Turn a node -> community assignment into groups of node indices, the same
+// shape `tarjan_scc` returns.
+pub(crate) fn membership_to_partition(membership: &[usize]) -> Partition {
+    let (membership, count) = renumber(membership);
+    let mut partition = vec![Vec::new(); count];
+    for (node, &community) in membership.iter().enumerate() {
+        partition[community].push(NodeIndex::new(node));
+    }
+    partition
+}
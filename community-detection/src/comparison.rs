@@ -0,0 +1,172 @@
+use crate::error::{RecordError, RecordErrorKind, Result};
+use csv::Reader;
+use std::collections::HashMap;
+
+// Agreement between a detected partition and a reference partition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PartitionComparison {
+    // Normalized mutual information in [0, 1], 1 for identical partitions
+    pub nmi: f64,
+    // Adjusted Rand index, 1 for identical partitions and around 0 for random ones
+    pub ari: f64,
+    // Variation of information in nats, 0 for identical partitions
+    pub vi: f64,
+}
+
+// Load a `user,community` CSV (with a header row) as a partition, e.g. a
+// ground truth or the output of `CommunityDetector::export_assignments`.
+// When every community is a number the ids are kept as they are; otherwise
+// community names may be arbitrary strings and are mapped to ids in order of
+// first appearance.
+pub fn load_partition_csv(filename: &str) -> Result<HashMap<String, usize>> {
+    let mut rows = Vec::new();
+    for record in Reader::from_path(filename)?.into_records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let (Some(user), Some(community)) = (record.get(0), record.get(1)) else {
+            return Err(RecordError::new(line, RecordErrorKind::MissingColumn(1)).into());
+        };
+        rows.push((user.to_string(), community.trim().to_string()));
+    }
+
+    let numeric: Option<Vec<usize>> = rows
+        .iter()
+        .map(|(_, community)| community.parse().ok())
+        .collect();
+    if let Some(ids) = numeric {
+        return Ok(rows.into_iter().map(|(user, _)| user).zip(ids).collect());
+    }
+
+    let mut community_ids = HashMap::new();
+    let mut labels = HashMap::new();
+    for (user, community) in rows {
+        let next = community_ids.len();
+        let community = *community_ids.entry(community).or_insert(next);
+        labels.insert(user, community);
+    }
+    Ok(labels)
+}
+
+// Compare two partitions on the users they have in common. All three scores
+// are NaN when the partitions share no users.
+pub fn compare_partitions(
+    labels: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+) -> PartitionComparison {
+    let table = ContingencyTable::new(labels, reference);
+    if table.total == 0 {
+        return PartitionComparison {
+            nmi: f64::NAN,
+            ari: f64::NAN,
+            vi: f64::NAN,
+        };
+    }
+    let mutual_information = table.mutual_information();
+    let entropy_a = entropy(&table.row_sums, table.total);
+    let entropy_b = entropy(&table.column_sums, table.total);
+
+    PartitionComparison {
+        nmi: if entropy_a + entropy_b > 0.0 {
+            2.0 * mutual_information / (entropy_a + entropy_b)
+        } else {
+            1.0
+        },
+        ari: table.adjusted_rand_index(),
+        vi: (entropy_a + entropy_b - 2.0 * mutual_information).max(0.0),
+    }
+}
+
+pub fn normalized_mutual_information(
+    labels: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+) -> f64 {
+    compare_partitions(labels, reference).nmi
+}
+
+pub fn adjusted_rand_index(
+    labels: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+) -> f64 {
+    compare_partitions(labels, reference).ari
+}
+
+pub fn variation_of_information(
+    labels: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+) -> f64 {
+    compare_partitions(labels, reference).vi
+}
+
+struct ContingencyTable {
+    cells: HashMap<(usize, usize), usize>,
+    row_sums: HashMap<usize, usize>,
+    column_sums: HashMap<usize, usize>,
+    total: usize,
+}
+
+impl ContingencyTable {
+    fn new(labels: &HashMap<String, usize>, reference: &HashMap<String, usize>) -> Self {
+        let mut table = ContingencyTable {
+            cells: HashMap::new(),
+            row_sums: HashMap::new(),
+            column_sums: HashMap::new(),
+            total: 0,
+        };
+
+        for (user, &row) in labels {
+            if let Some(&column) = reference.get(user) {
+                *table.cells.entry((row, column)).or_insert(0) += 1;
+                *table.row_sums.entry(row).or_insert(0) += 1;
+                *table.column_sums.entry(column).or_insert(0) += 1;
+                table.total += 1;
+            }
+        }
+
+        table
+    }
+
+    fn mutual_information(&self) -> f64 {
+        let total = self.total as f64;
+        self.cells
+            .iter()
+            .map(|(&(row, column), &count)| {
+                let count = count as f64;
+                let expected = self.row_sums[&row] as f64 * self.column_sums[&column] as f64;
+                count / total * (total * count / expected).ln()
+            })
+            .sum()
+    }
+
+    fn adjusted_rand_index(&self) -> f64 {
+        let index: f64 = self.cells.values().map(|&count| pairs(count)).sum();
+        let rows: f64 = self.row_sums.values().map(|&count| pairs(count)).sum();
+        let columns: f64 = self.column_sums.values().map(|&count| pairs(count)).sum();
+        let all = pairs(self.total);
+        if all == 0.0 {
+            return 1.0;
+        }
+
+        let expected = rows * columns / all;
+        let maximum = (rows + columns) / 2.0;
+        if maximum == expected {
+            1.0
+        } else {
+            (index - expected) / (maximum - expected)
+        }
+    }
+}
+
+fn pairs(count: usize) -> f64 {
+    let count = count as f64;
+    count * (count - 1.0) / 2.0
+}
+
+fn entropy(sums: &HashMap<usize, usize>, total: usize) -> f64 {
+    let total = total as f64;
+    sums.values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.ln()
+        })
+        .sum()
+}
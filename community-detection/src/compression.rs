@@ -0,0 +1,91 @@
+use std::fs::File;
+use std::io::{BufReader, Read, Result};
+use std::path::Path;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+// Compression of an input file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    // Detect the compression of `path` from its extension (`.gz`, `.zst`),
+    // falling back to the magic bytes at the start of the file.
+    pub fn detect(path: &Path) -> Result<Self> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("gz" | "gzip") => return Ok(Compression::Gzip),
+            Some("zst" | "zstd") => return Ok(Compression::Zstd),
+            _ => {}
+        }
+
+        let mut magic = Vec::with_capacity(ZSTD_MAGIC.len());
+        File::open(path)?
+            .take(ZSTD_MAGIC.len() as u64)
+            .read_to_end(&mut magic)?;
+        Ok(if magic.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if magic.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        })
+    }
+}
+
+// Decompressed contents of a file, decoded while it is read so that large
+// files never sit in memory as a whole.
+pub(crate) struct Decompressed(Box<dyn Read>);
+
+impl Decompressed {
+    // Returns `None` for uncompressed files.
+    pub fn open(path: &Path, compression: Compression) -> Result<Option<Self>> {
+        let file = BufReader::new(File::open(path)?);
+        let decoder = match compression {
+            Compression::None => return Ok(None),
+            Compression::Gzip => gzip(file)?,
+            Compression::Zstd => zstd(file)?,
+        };
+        Ok(Some(Decompressed(decoder)))
+    }
+}
+
+impl Read for Decompressed {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        self.0.read(buffer)
+    }
+}
+
+// Like `gzip -dc`, files with several concatenated members are read whole.
+#[cfg(feature = "gzip")]
+fn gzip(file: BufReader<File>) -> Result<Box<dyn Read>> {
+    Ok(Box::new(flate2::bufread::MultiGzDecoder::new(file)))
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gzip(_: BufReader<File>) -> Result<Box<dyn Read>> {
+    Err(unsupported("gzip"))
+}
+
+#[cfg(feature = "zstd")]
+fn zstd(file: BufReader<File>) -> Result<Box<dyn Read>> {
+    Ok(Box::new(zstd::stream::read::Decoder::with_buffer(file)?))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd(_: BufReader<File>) -> Result<Box<dyn Read>> {
+    Err(unsupported("zstd"))
+}
+
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn unsupported(feature: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("built without the `{}` feature", feature),
+    )
+}
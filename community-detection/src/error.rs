@@ -0,0 +1,105 @@
+use std::fmt;
+use std::path::PathBuf;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Errors raised while loading interaction data.
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Record(RecordError),
+    // The input does not match the configured schema, e.g. a named column
+    // is missing from the header
+    Schema(String),
+}
+
+// A single input row that could not be turned into an edge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordError {
+    // 1-based line number in the input
+    pub line: u64,
+    pub kind: RecordErrorKind,
+    // Input file the row was read from, when loading several files
+    pub file: Option<PathBuf>,
+}
+
+impl RecordError {
+    pub fn new(line: u64, kind: RecordErrorKind) -> Self {
+        RecordError {
+            line,
+            kind,
+            file: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RecordErrorKind {
+    // The row has no field at this 0-based column index
+    MissingColumn(usize),
+    // The weight field does not parse as a number
+    InvalidWeight(String),
+    // The row could not be parsed as CSV at all, e.g. invalid UTF-8
+    Malformed(String),
+    // Aggregating this row into its edge overflowed the weight type
+    WeightOverflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "I/O error: {}", error),
+            Error::Record(error) => error.fmt(f),
+            Error::Schema(message) => write!(f, "schema error: {}", message),
+        }
+    }
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}: ", file.display(), self.line)?,
+            None => write!(f, "line {}: ", self.line)?,
+        }
+        match &self.kind {
+            RecordErrorKind::MissingColumn(index) => write!(f, "missing column {}", index),
+            RecordErrorKind::InvalidWeight(value) => write!(f, "invalid weight {:?}", value),
+            RecordErrorKind::Malformed(message) => write!(f, "malformed row: {}", message),
+            RecordErrorKind::WeightOverflow => write!(f, "aggregated weight overflows"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Record(_) | Error::Schema(_) => None,
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(error: csv::Error) -> Self {
+        let line = error.position().map_or(0, |position| position.line());
+        let message = error.to_string();
+        match error.into_kind() {
+            csv::ErrorKind::Io(error) => Error::Io(error),
+            _ => Error::Record(RecordError::new(line, RecordErrorKind::Malformed(message))),
+        }
+    }
+}
+
+impl From<RecordError> for Error {
+    fn from(error: RecordError) -> Self {
+        Error::Record(error)
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+mod parquet;
+
+// File formats for community assignments and summaries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    // One JSON object per line
+    JsonLines,
+    // Uncompressed, plain-encoded Parquet with a single row group
+    Parquet,
+}
+
+impl ExportFormat {
+    // Guess the format from the file extension
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" | "ndjson" => Some(ExportFormat::JsonLines),
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+// Values of one column of an exported table
+pub(crate) enum Values {
+    Text(Vec<String>),
+    Integer(Vec<i64>),
+    Float(Vec<f64>),
+}
+
+impl Values {
+    fn len(&self) -> usize {
+        match self {
+            Values::Text(values) => values.len(),
+            Values::Integer(values) => values.len(),
+            Values::Float(values) => values.len(),
+        }
+    }
+}
+
+// A table with named columns of equal length
+pub(crate) struct Table {
+    pub columns: Vec<(&'static str, Values)>,
+}
+
+impl Table {
+    fn rows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, values)| values.len())
+    }
+
+    pub fn write(&self, filename: &str, format: ExportFormat) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        match format {
+            ExportFormat::Csv => self.write_csv(&mut writer)?,
+            ExportFormat::JsonLines => self.write_json_lines(&mut writer)?,
+            ExportFormat::Parquet => parquet::write(self, &mut writer)?,
+        }
+        writer.flush()
+    }
+
+    fn write_csv<O: Write>(&self, output: O) -> std::io::Result<()> {
+        let mut writer = csv::Writer::from_writer(output);
+        writer.write_record(self.columns.iter().map(|(name, _)| name))?;
+        for row in 0..self.rows() {
+            writer.write_record(self.columns.iter().map(|(_, values)| match values {
+                Values::Text(values) => values[row].clone(),
+                Values::Integer(values) => values[row].to_string(),
+                Values::Float(values) => values[row].to_string(),
+            }))?;
+        }
+        writer.flush()
+    }
+
+    fn write_json_lines<O: Write>(&self, writer: &mut O) -> std::io::Result<()> {
+        for row in 0..self.rows() {
+            let fields: Vec<String> = self
+                .columns
+                .iter()
+                .map(|(name, values)| {
+                    let value = match values {
+                        Values::Text(values) => json_string(&values[row]),
+                        Values::Integer(values) => values[row].to_string(),
+                        // JSON has no NaN or infinity
+                        Values::Float(values) if !values[row].is_finite() => "null".to_string(),
+                        Values::Float(values) => values[row].to_string(),
+                    };
+                    format!("{}:{}", json_string(name), value)
+                })
+                .collect();
+            writeln!(writer, "{{{}}}", fields.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
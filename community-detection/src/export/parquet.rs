@@ -0,0 +1,247 @@
+use super::{Table, Values};
+use std::io::{Result, Write};
+
+// A minimal Parquet writer: one row group, required columns, plain encoding,
+// no compression. Metadata is encoded with the Thrift compact protocol, see
+// https://github.com/apache/parquet-format/blob/master/src/main/thrift/parquet.thrift
+
+const MAGIC: &[u8] = b"PAR1";
+// Values per data page, which keeps page sizes far from the i32 limit
+const PAGE_VALUES: usize = 64 * 1024;
+
+// Enum values from parquet.thrift
+const TYPE_INT64: i32 = 2;
+const TYPE_DOUBLE: i32 = 5;
+const TYPE_BYTE_ARRAY: i32 = 6;
+const REPETITION_REQUIRED: i32 = 0;
+const CONVERTED_TYPE_UTF8: i32 = 0;
+const ENCODING_PLAIN: i32 = 0;
+const ENCODING_RLE: i32 = 3;
+const CODEC_UNCOMPRESSED: i32 = 0;
+const PAGE_TYPE_DATA: i32 = 0;
+
+// Position of a written column chunk in the file
+struct ColumnChunk {
+    offset: u64,
+    size: u64,
+}
+
+pub(crate) fn write<O: Write>(table: &Table, writer: &mut O) -> Result<()> {
+    let rows = table.rows();
+    writer.write_all(MAGIC)?;
+    let mut offset = MAGIC.len() as u64;
+
+    let mut chunks = Vec::with_capacity(table.columns.len());
+    for (_, values) in &table.columns {
+        let start = offset;
+        // Always at least one page, so that empty tables are still readable
+        for page in 0..rows.div_ceil(PAGE_VALUES).max(1) {
+            let range = page * PAGE_VALUES..((page + 1) * PAGE_VALUES).min(rows);
+            let data = encode_values(values, range.clone());
+            let header = page_header(data.len(), range.len());
+            writer.write_all(&header)?;
+            writer.write_all(&data)?;
+            offset += (header.len() + data.len()) as u64;
+        }
+        chunks.push(ColumnChunk {
+            offset: start,
+            size: offset - start,
+        });
+    }
+
+    let footer = file_metadata(table, &chunks);
+    writer.write_all(&footer)?;
+    writer.write_all(&(footer.len() as u32).to_le_bytes())?;
+    writer.write_all(MAGIC)
+}
+
+fn physical_type(values: &Values) -> i32 {
+    match values {
+        Values::Text(_) => TYPE_BYTE_ARRAY,
+        Values::Integer(_) => TYPE_INT64,
+        Values::Float(_) => TYPE_DOUBLE,
+    }
+}
+
+// Plain encoding; required columns have no repetition or definition levels.
+fn encode_values(values: &Values, range: std::ops::Range<usize>) -> Vec<u8> {
+    let mut data = Vec::new();
+    match values {
+        Values::Text(values) => {
+            for value in &values[range] {
+                data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+                data.extend_from_slice(value.as_bytes());
+            }
+        }
+        Values::Integer(values) => {
+            for value in &values[range] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        Values::Float(values) => {
+            for value in &values[range] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+    data
+}
+
+fn page_header(size: usize, values: usize) -> Vec<u8> {
+    let mut header = CompactWriter::default();
+    header.i32_field(1, PAGE_TYPE_DATA);
+    header.i32_field(2, size as i32);
+    header.i32_field(3, size as i32);
+    header.struct_field(5);
+    header.i32_field(1, values as i32);
+    header.i32_field(2, ENCODING_PLAIN);
+    header.i32_field(3, ENCODING_RLE);
+    header.i32_field(4, ENCODING_RLE);
+    header.end_struct();
+    header.end_struct();
+    header.buffer
+}
+
+fn file_metadata(table: &Table, chunks: &[ColumnChunk]) -> Vec<u8> {
+    let rows = table.rows() as i64;
+    let mut footer = CompactWriter::default();
+    footer.i32_field(1, 1);
+
+    footer.list_field(2, COMPACT_STRUCT, table.columns.len() + 1);
+    footer.begin_struct();
+    footer.binary_field(4, b"schema");
+    footer.i32_field(5, table.columns.len() as i32);
+    footer.end_struct();
+    for (name, values) in &table.columns {
+        footer.begin_struct();
+        footer.i32_field(1, physical_type(values));
+        footer.i32_field(3, REPETITION_REQUIRED);
+        footer.binary_field(4, name.as_bytes());
+        if let Values::Text(_) = values {
+            footer.i32_field(6, CONVERTED_TYPE_UTF8);
+        }
+        footer.end_struct();
+    }
+
+    footer.i64_field(3, rows);
+
+    footer.list_field(4, COMPACT_STRUCT, 1);
+    footer.begin_struct();
+    footer.list_field(1, COMPACT_STRUCT, chunks.len());
+    for ((name, values), chunk) in table.columns.iter().zip(chunks) {
+        footer.begin_struct();
+        footer.i64_field(2, chunk.offset as i64);
+        footer.struct_field(3);
+        footer.i32_field(1, physical_type(values));
+        footer.list_field(2, COMPACT_I32, 2);
+        footer.varint(zigzag(ENCODING_PLAIN as i64));
+        footer.varint(zigzag(ENCODING_RLE as i64));
+        footer.list_field(3, COMPACT_BINARY, 1);
+        footer.binary(name.as_bytes());
+        footer.i32_field(4, CODEC_UNCOMPRESSED);
+        footer.i64_field(5, rows);
+        footer.i64_field(6, chunk.size as i64);
+        footer.i64_field(7, chunk.size as i64);
+        footer.i64_field(9, chunk.offset as i64);
+        footer.end_struct();
+        footer.end_struct();
+    }
+    let total_size: u64 = chunks.iter().map(|chunk| chunk.size).sum();
+    footer.i64_field(2, total_size as i64);
+    footer.i64_field(3, rows);
+    footer.end_struct();
+
+    footer.binary_field(6, b"community-detection");
+    footer.end_struct();
+    footer.buffer
+}
+
+// Thrift compact protocol type ids
+const COMPACT_I32: u8 = 5;
+const COMPACT_I64: u8 = 6;
+const COMPACT_BINARY: u8 = 8;
+const COMPACT_LIST: u8 = 9;
+const COMPACT_STRUCT: u8 = 12;
+
+// Writes Thrift structs in the compact protocol. Field ids are delta encoded
+// against the previous field of the same struct, so every struct keeps its
+// own last id.
+#[derive(Default)]
+struct CompactWriter {
+    buffer: Vec<u8>,
+    last_field: i16,
+    enclosing: Vec<i16>,
+}
+
+impl CompactWriter {
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.buffer.push(value as u8);
+    }
+
+    fn field_header(&mut self, id: i16, kind: u8) {
+        let delta = id - self.last_field;
+        if (1..=15).contains(&delta) {
+            self.buffer.push((delta as u8) << 4 | kind);
+        } else {
+            self.buffer.push(kind);
+            self.varint(zigzag(id as i64));
+        }
+        self.last_field = id;
+    }
+
+    fn i32_field(&mut self, id: i16, value: i32) {
+        self.field_header(id, COMPACT_I32);
+        self.varint(zigzag(value as i64));
+    }
+
+    fn i64_field(&mut self, id: i16, value: i64) {
+        self.field_header(id, COMPACT_I64);
+        self.varint(zigzag(value));
+    }
+
+    fn binary(&mut self, value: &[u8]) {
+        self.varint(value.len() as u64);
+        self.buffer.extend_from_slice(value);
+    }
+
+    fn binary_field(&mut self, id: i16, value: &[u8]) {
+        self.field_header(id, COMPACT_BINARY);
+        self.binary(value);
+    }
+
+    // Followed by `len` elements written without field headers
+    fn list_field(&mut self, id: i16, element: u8, len: usize) {
+        self.field_header(id, COMPACT_LIST);
+        if len < 15 {
+            self.buffer.push((len as u8) << 4 | element);
+        } else {
+            self.buffer.push(0xf0 | element);
+            self.varint(len as u64);
+        }
+    }
+
+    // A struct inside a list; `end_struct` closes it
+    fn begin_struct(&mut self) {
+        self.enclosing.push(self.last_field);
+        self.last_field = 0;
+    }
+
+    fn struct_field(&mut self, id: i16) {
+        self.field_header(id, COMPACT_STRUCT);
+        self.begin_struct();
+    }
+
+    // Also ends the outermost struct, which is never begun explicitly
+    fn end_struct(&mut self) {
+        self.buffer.push(0);
+        self.last_field = self.enclosing.pop().unwrap_or(0);
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
@@ -0,0 +1,63 @@
+use super::{ImportedEdge, ImportedGraph};
+use crate::error::{RecordError, RecordErrorKind};
+
+// Edge lists are always read as directed; load them into an undirected
+// detector to merge both directions.
+pub(crate) fn parse(text: &str) -> ImportedGraph {
+    let mut graph = ImportedGraph::default();
+    for (index, row) in text.lines().enumerate() {
+        let line = index as u64 + 1;
+        let row = row.trim();
+        if row.is_empty() || row.starts_with('#') || row.starts_with('%') {
+            continue;
+        }
+
+        // Columns after the weight, e.g. KONECT timestamps, are ignored.
+        let mut fields = row.split_whitespace();
+        let (Some(source), Some(target)) = (fields.next(), fields.next()) else {
+            graph
+                .bad_rows
+                .push(RecordError::new(line, RecordErrorKind::MissingColumn(1)));
+            continue;
+        };
+        let edge = ImportedEdge {
+            source: graph.node(source),
+            target: graph.node(target),
+            weight: fields.next().map(str::to_string),
+            directed: true,
+            line,
+        };
+        graph.edges.push(edge);
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines_are_skipped() {
+        let graph = parse("# SNAP header\n% KONECT header\n\n1 2\n  2\t3 0.5  \n");
+        assert_eq!(graph.names, ["1", "2", "3"]);
+        assert_eq!(
+            graph.edges_by_name(),
+            [("1", "2", None, true), ("2", "3", Some("0.5"), true)]
+        );
+        assert_eq!(graph.edges[1].line, 5);
+    }
+
+    #[test]
+    fn columns_after_the_weight_are_ignored() {
+        let graph = parse("a b 3 1262304000\n");
+        assert_eq!(graph.edges_by_name(), [("a", "b", Some("3"), true)]);
+    }
+
+    #[test]
+    fn rows_without_a_target_are_bad() {
+        let graph = parse("a b\nlonely\nb c\n");
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.bad_lines(), [2]);
+        assert_eq!(graph.bad_rows[0].kind, RecordErrorKind::MissingColumn(1));
+    }
+}
@@ -0,0 +1,80 @@
+use super::degrees;
+use super::xml::escape;
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+use std::collections::HashMap;
+use std::io::Write;
+
+// GEXF 1.2 as read by Gephi. Nodes are numbered by index and labelled with
+// their name; `community` and `degree` are node attributes, and the edge
+// weight uses the native `weight` attribute.
+pub(crate) fn write<W: EdgeWeight, Ty: EdgeType, O: Write>(
+    graph: &Graph<String, W, Ty>,
+    labels: &HashMap<String, usize>,
+    writer: &mut O,
+) -> std::io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<gexf xmlns="http://www.gexf.net/1.2draft" version="1.2">"#
+    )?;
+    let edge_type = if Ty::is_directed() {
+        "directed"
+    } else {
+        "undirected"
+    };
+    writeln!(
+        writer,
+        r#"  <graph mode="static" defaultedgetype="{}">"#,
+        edge_type
+    )?;
+    writeln!(writer, r#"    <attributes class="node">"#)?;
+    writeln!(
+        writer,
+        r#"      <attribute id="community" title="community" type="long"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"      <attribute id="degree" title="degree" type="long"/>"#
+    )?;
+    writeln!(writer, "    </attributes>")?;
+
+    writeln!(writer, "    <nodes>")?;
+    for (node, degree) in graph.node_indices().zip(degrees(graph)) {
+        write!(
+            writer,
+            r#"      <node id="{}" label="{}"><attvalues>"#,
+            node.index(),
+            escape(&graph[node])
+        )?;
+        if let Some(community) = labels.get(&graph[node]) {
+            write!(
+                writer,
+                r#"<attvalue for="community" value="{}"/>"#,
+                community
+            )?;
+        }
+        writeln!(
+            writer,
+            r#"<attvalue for="degree" value="{}"/></attvalues></node>"#,
+            degree
+        )?;
+    }
+    writeln!(writer, "    </nodes>")?;
+
+    writeln!(writer, "    <edges>")?;
+    for (id, edge) in graph.raw_edges().iter().enumerate() {
+        writeln!(
+            writer,
+            r#"      <edge id="{}" source="{}" target="{}" weight="{}"/>"#,
+            id,
+            edge.source().index(),
+            edge.target().index(),
+            edge.weight.to_f64()
+        )?;
+    }
+    writeln!(writer, "    </edges>")?;
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</gexf>")
+}
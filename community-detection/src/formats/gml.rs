@@ -0,0 +1,253 @@
+use super::{ImportedEdge, ImportedGraph, malformed};
+use crate::error::{Error, RecordError, Result};
+use std::collections::HashMap;
+
+// A GML value: a number or string, or a bracketed list of key-value pairs
+enum Value<'a> {
+    Scalar(&'a str),
+    List(Vec<Entry<'a>>),
+}
+
+struct Entry<'a> {
+    key: &'a str,
+    value: Value<'a>,
+    line: u64,
+}
+
+// Nodes are named by their `label`, or by their `id` when they have none.
+// The edge weight is read from `weight`, or from `value` as in the Newman
+// datasets. `directed 1` on the graph makes all edges directed.
+pub(crate) fn parse(text: &str) -> Result<ImportedGraph> {
+    let mut tokens = Tokens::new(text);
+    let entries = parse_list(&mut tokens, None, 0)?;
+    let Some(Value::List(entries)) = entries
+        .into_iter()
+        .find(|entry| entry.key == "graph")
+        .map(|entry| entry.value)
+    else {
+        return Err(Error::Schema("no graph in GML input".to_string()));
+    };
+
+    let directed = scalar(&entries, "directed") == Some("1");
+
+    let mut graph = ImportedGraph::default();
+    let mut ids = HashMap::new();
+    for entry in &entries {
+        if let ("node", Value::List(node)) = (entry.key, &entry.value) {
+            let id =
+                scalar(node, "id").ok_or_else(|| malformed(entry.line, "node without an id"))?;
+            let name = scalar(node, "label").unwrap_or(id);
+            ids.insert(id, graph.node(name));
+        }
+    }
+
+    for entry in &entries {
+        let ("edge", Value::List(edge)) = (entry.key, &entry.value) else {
+            continue;
+        };
+        let endpoint = |key| scalar(edge, key).and_then(|id| ids.get(id).copied());
+        let (Some(source), Some(target)) = (endpoint("source"), endpoint("target")) else {
+            let error = malformed(entry.line, "edge without known source and target");
+            graph.bad_rows.push(error);
+            continue;
+        };
+        graph.edges.push(ImportedEdge {
+            source,
+            target,
+            weight: scalar(edge, "weight")
+                .or_else(|| scalar(edge, "value"))
+                .map(str::to_string),
+            directed,
+            line: entry.line,
+        });
+    }
+
+    Ok(graph)
+}
+
+fn scalar<'a>(entries: &[Entry<'a>], key: &str) -> Option<&'a str> {
+    entries.iter().find_map(|entry| match entry.value {
+        Value::Scalar(value) if entry.key == key => Some(value),
+        _ => None,
+    })
+}
+
+// Graphs only nest a few levels deep (`graph`, `node`, `graphics`), so
+// anything deeper is rejected before the recursion can exhaust the stack.
+const MAX_DEPTH: usize = 64;
+
+// Parse key-value pairs up to the `]` closing the list opened on `open`, or
+// to the end of the input at the top level. `depth` counts the open lists.
+fn parse_list<'a>(
+    tokens: &mut Tokens<'a>,
+    open: Option<u64>,
+    depth: usize,
+) -> std::result::Result<Vec<Entry<'a>>, RecordError> {
+    let mut entries = Vec::new();
+    loop {
+        let (key, line) = match (tokens.next()?, open) {
+            (None, None) => return Ok(entries),
+            (None, Some(line)) => return Err(malformed(line, "unclosed '['")),
+            (Some(("]", _)), Some(_)) => return Ok(entries),
+            (Some(("]", line)), None) => return Err(malformed(line, "unexpected ']'")),
+            (Some(token), _) => token,
+        };
+        let value = match tokens.next()? {
+            Some(("[", line)) if depth == MAX_DEPTH => {
+                return Err(malformed(
+                    line,
+                    format!("lists nested more than {} deep", MAX_DEPTH),
+                ));
+            }
+            Some(("[", line)) => Value::List(parse_list(tokens, Some(line), depth + 1)?),
+            Some(("]", _)) | None => {
+                return Err(malformed(line, format!("missing value for {:?}", key)));
+            }
+            Some((value, _)) => Value::Scalar(value.trim_matches('"')),
+        };
+        entries.push(Entry { key, value, line });
+    }
+}
+
+// Splits GML into keys, values and brackets, keeping quoted strings whole
+// and dropping `#` comment lines
+struct Tokens<'a> {
+    text: &'a str,
+    position: usize,
+    line: u64,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(text: &'a str) -> Self {
+        Tokens {
+            text,
+            position: 0,
+            line: 1,
+        }
+    }
+
+    fn next(&mut self) -> std::result::Result<Option<(&'a str, u64)>, RecordError> {
+        let bytes = self.text.as_bytes();
+        loop {
+            match bytes.get(self.position) {
+                None => return Ok(None),
+                Some(b'\n') => {
+                    self.line += 1;
+                    self.position += 1;
+                }
+                Some(byte) if byte.is_ascii_whitespace() => self.position += 1,
+                Some(b'#') => {
+                    let rest = &self.text[self.position..];
+                    self.position += rest.find('\n').unwrap_or(rest.len());
+                }
+                Some(_) => break,
+            }
+        }
+
+        let start = self.position;
+        let line = self.line;
+        let end = match bytes[start] {
+            b'[' | b']' => start + 1,
+            b'"' => {
+                let Some(len) = self.text[start + 1..].find('"') else {
+                    return Err(malformed(line, "unterminated string"));
+                };
+                let string = &self.text[start..start + len + 2];
+                self.line += string.bytes().filter(|&byte| byte == b'\n').count() as u64;
+                start + len + 2
+            }
+            _ => {
+                let rest = &bytes[start..];
+                let len = rest
+                    .iter()
+                    .position(|byte| byte.is_ascii_whitespace() || matches!(byte, b'[' | b']'))
+                    .unwrap_or(rest.len());
+                start + len
+            }
+        };
+        self.position = end;
+        Ok(Some((&self.text[start..end], line)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nodes_are_named_by_label_or_id() {
+        let text = r#"# written by hand
+Creator "test"
+graph [
+  # the Newman datasets use `value` for weights
+  node [ id 1 label "Ann Lee" ]
+  node [ id 2 ]
+  edge [ source 1 target 2 value 3 ]
+  edge [ source 2 target 1 weight 1.5 value 9 ]
+]"#;
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.names, ["Ann Lee", "2"]);
+        assert_eq!(
+            graph.edges_by_name(),
+            [
+                ("Ann Lee", "2", Some("3"), false),
+                ("2", "Ann Lee", Some("1.5"), false)
+            ]
+        );
+        assert_eq!(graph.edges[1].line, 8);
+    }
+
+    #[test]
+    fn directed_graphs() {
+        let text = "graph [ directed 1 node [ id 0 ] node [ id 1 ] edge [ source 0 target 1 ] ]";
+        assert_eq!(
+            parse(text).unwrap().edges_by_name(),
+            [("0", "1", None, true)]
+        );
+    }
+
+    #[test]
+    fn quoted_labels_keep_brackets_and_comments() {
+        let text = "graph [\n node [ id 0 label \"a [x] # y\" ]\n node [ id 1 label \"b\" ]\n edge [ source 0 target 1 ]\n]";
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.names, ["a [x] # y", "b"]);
+    }
+
+    #[test]
+    fn edges_to_unknown_nodes_are_bad() {
+        let text =
+            "graph [\n node [ id 0 ]\n edge [ source 0 target 7 ]\n edge [ source 0 target 0 ]\n]";
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.bad_lines(), [3]);
+    }
+
+    #[test]
+    fn structural_errors() {
+        assert!(matches!(
+            parse("graph [ node [ id 0 ]"),
+            Err(Error::Record(_))
+        ));
+        assert!(matches!(parse("graph [ ] ]"), Err(Error::Record(_))));
+        assert!(matches!(
+            parse("graph [ node [ label \"x\" ] ]"),
+            Err(Error::Record(_))
+        ));
+        assert!(matches!(parse("Creator \"nobody\""), Err(Error::Schema(_))));
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let mut text = "graph [ node [ id 0 ] ".to_string();
+        text.push_str(&"x [ ".repeat(200_000));
+        text.push_str(&"] ".repeat(200_000));
+        text.push(']');
+        match parse(&text) {
+            Err(Error::Record(error)) => assert_eq!(error.line, 1),
+            _ => panic!("expected a malformed record"),
+        }
+
+        let nested = format!("graph [ {}{}]", "x [ ".repeat(62), "] ".repeat(62));
+        assert!(parse(&nested).is_ok());
+    }
+}
@@ -0,0 +1,295 @@
+use super::xml::{Event, Reader, attribute, escape, local_name};
+use super::{ImportedEdge, ImportedGraph, degrees, malformed};
+use crate::error::Result;
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+
+// Nodes are named by their `id`. The edge weight is the `<data>` whose key
+// declares `attr.name="weight"`; edges without one get the key's `<default>`,
+// or the default weight when it has none. Nested graphs, hyperedges and
+// ports are not supported.
+pub(crate) fn parse(text: &str) -> Result<ImportedGraph> {
+    let mut graph = ImportedGraph::default();
+    let mut reader = Reader::new(text);
+    let mut weight_keys = HashSet::new();
+    let mut weight_default: Option<String> = None;
+    // Whether a weight `<key>`, and the `<default>` inside it, are open
+    let mut in_weight_key = false;
+    let mut in_weight_default = false;
+    let mut directed = true;
+    // Edge whose `<data>` children are being read, and the key of the
+    // `<data>` element that is open
+    let mut edge: Option<ImportedEdge> = None;
+    let mut data_key: Option<String> = None;
+
+    while let Some(event) = reader.next_event()? {
+        match event {
+            Event::Start {
+                name,
+                attributes,
+                empty,
+                line,
+            } => match local_name(name) {
+                "key" => {
+                    let applies =
+                        matches!(attribute(&attributes, "for"), Some("edge" | "all") | None);
+                    if let (true, Some("weight"), Some(id)) = (
+                        applies,
+                        attribute(&attributes, "attr.name"),
+                        attribute(&attributes, "id"),
+                    ) {
+                        weight_keys.insert(id.to_string());
+                        in_weight_key = !empty;
+                    }
+                }
+                "default" if in_weight_key && !empty => in_weight_default = true,
+                "graph" => {
+                    directed = attribute(&attributes, "edgedefault") != Some("undirected");
+                }
+                "node" => {
+                    let id = attribute(&attributes, "id")
+                        .ok_or_else(|| malformed(line, "<node> without an id"))?;
+                    graph.node(id);
+                }
+                "edge" => {
+                    let (Some(source), Some(target)) = (
+                        attribute(&attributes, "source"),
+                        attribute(&attributes, "target"),
+                    ) else {
+                        graph
+                            .bad_rows
+                            .push(malformed(line, "<edge> without source and target"));
+                        continue;
+                    };
+                    let new_edge = ImportedEdge {
+                        source: graph.node(source),
+                        target: graph.node(target),
+                        weight: None,
+                        directed: match attribute(&attributes, "directed") {
+                            Some(value) => value == "true",
+                            None => directed,
+                        },
+                        line,
+                    };
+                    if empty {
+                        graph.edges.push(with_default(new_edge, &weight_default));
+                    } else {
+                        edge = Some(new_edge);
+                    }
+                }
+                "data" if edge.is_some() && !empty => {
+                    data_key = attribute(&attributes, "key").map(str::to_string);
+                }
+                _ => {}
+            },
+            Event::Text(text) if in_weight_default => {
+                weight_default = Some(text.trim().to_string());
+            }
+            Event::Text(text) => {
+                if let (Some(edge), Some(key)) = (edge.as_mut(), &data_key)
+                    && weight_keys.contains(key)
+                {
+                    edge.weight = Some(text.trim().to_string());
+                }
+            }
+            Event::End { name } => match local_name(name) {
+                "data" => data_key = None,
+                "default" => in_weight_default = false,
+                "key" => in_weight_key = false,
+                "edge" => {
+                    if let Some(edge) = edge.take() {
+                        graph.edges.push(with_default(edge, &weight_default));
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    Ok(graph)
+}
+
+fn with_default(mut edge: ImportedEdge, weight_default: &Option<String>) -> ImportedEdge {
+    if edge.weight.is_none() {
+        edge.weight = weight_default.clone();
+    }
+    edge
+}
+
+// Nodes use their name as id, so `parse` reads the file back into the same
+// graph. Every node carries `label` and `degree`, labelled nodes also
+// `community`, and every edge its `weight`.
+pub(crate) fn write<W: EdgeWeight, Ty: EdgeType, O: Write>(
+    graph: &Graph<String, W, Ty>,
+    labels: &HashMap<String, usize>,
+    writer: &mut O,
+) -> std::io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="label" for="node" attr.name="label" attr.type="string"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="community" for="node" attr.name="community" attr.type="long"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="degree" for="node" attr.name="degree" attr.type="long"/>"#
+    )?;
+    writeln!(
+        writer,
+        r#"  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>"#
+    )?;
+    let edge_default = if Ty::is_directed() {
+        "directed"
+    } else {
+        "undirected"
+    };
+    writeln!(writer, r#"  <graph id="G" edgedefault="{}">"#, edge_default)?;
+
+    let names: Vec<String> = graph.node_weights().map(|name| escape(name)).collect();
+    for (node, degree) in graph.node_indices().zip(degrees(graph)) {
+        let name = &names[node.index()];
+        write!(
+            writer,
+            r#"    <node id="{}"><data key="label">{}</data>"#,
+            name, name
+        )?;
+        if let Some(community) = labels.get(&graph[node]) {
+            write!(writer, r#"<data key="community">{}</data>"#, community)?;
+        }
+        writeln!(writer, r#"<data key="degree">{}</data></node>"#, degree)?;
+    }
+    for edge in graph.raw_edges() {
+        writeln!(
+            writer,
+            r#"    <edge source="{}" target="{}"><data key="weight">{}</data></edge>"#,
+            names[edge.source().index()],
+            names[edge.target().index()],
+            edge.weight.to_f64()
+        )?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="w" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="nw" for="node" attr.name="weight" attr.type="double"/>
+  <key id="c" for="edge" attr.name="color" attr.type="string"/>
+"#;
+
+    #[test]
+    fn weights_come_from_the_weight_key() {
+        let text = format!(
+            r#"{}  <graph edgedefault="directed">
+    <node id="a"><data key="nw">9</data></node>
+    <node id="b"/>
+    <edge source="a" target="b"><data key="c">red</data><data key="w"> 2.5 </data></edge>
+    <edge source="b" target="a"><data key="c">3</data></edge>
+  </graph>
+</graphml>"#,
+            HEADER
+        );
+        let graph = parse(&text).unwrap();
+        assert_eq!(graph.names, ["a", "b"]);
+        assert_eq!(
+            graph.edges_by_name(),
+            [("a", "b", Some("2.5"), true), ("b", "a", None, true)]
+        );
+        assert_eq!(graph.edges[0].line, 9);
+    }
+
+    #[test]
+    fn edges_without_data_get_the_key_default() {
+        let text = r#"<graphml>
+  <key id="d1" for="edge" attr.name="weight" attr.type="int">
+    <desc>interaction count</desc>
+    <default>4</default>
+  </key>
+  <graph edgedefault="directed">
+    <edge source="a" target="b"/>
+    <edge source="b" target="c"><data key="d1">7</data></edge>
+    <edge source="c" target="a"></edge>
+  </graph>
+</graphml>"#;
+        let graph = parse(text).unwrap();
+        assert_eq!(
+            graph.edges_by_name(),
+            [
+                ("a", "b", Some("4"), true),
+                ("b", "c", Some("7"), true),
+                ("c", "a", Some("4"), true)
+            ]
+        );
+    }
+
+    #[test]
+    fn edgedefault_and_directed_override() {
+        let text = format!(
+            r#"{}  <graph edgedefault="undirected">
+    <edge source="a" target="b"/>
+    <edge source="b" target="c" directed="true"/>
+  </graph>
+</graphml>"#,
+            HEADER
+        );
+        let graph = parse(&text).unwrap();
+        assert_eq!(
+            graph.edges_by_name(),
+            [("a", "b", None, false), ("b", "c", None, true)]
+        );
+
+        let text =
+            r#"<graphml><graph><edge source="a" target="b" directed="false"/></graph></graphml>"#;
+        assert_eq!(
+            parse(text).unwrap().edges_by_name(),
+            [("a", "b", None, false)]
+        );
+    }
+
+    #[test]
+    fn unknown_vertices_are_added() {
+        let text =
+            r#"<graphml><graph><node id="a"/><edge source="a" target="z"/></graph></graphml>"#;
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.names, ["a", "z"]);
+        assert_eq!(graph.edges.len(), 1);
+    }
+
+    #[test]
+    fn edges_without_endpoints_are_bad() {
+        let text = "<graphml><graph>\n<edge source=\"a\"/>\n<edge source=\"a\" target=\"b\"/>\n</graph></graphml>";
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.bad_lines(), [2]);
+    }
+
+    #[test]
+    fn ids_with_entities_and_namespaces() {
+        let text = r#"<g:graphml xmlns:g="http://graphml.graphdrawing.org/xmlns"><g:graph>
+  <g:node id="Tom &amp; Jerry"/><g:edge source="Tom &amp; Jerry" target="&#233;mile"/>
+</g:graph></g:graphml>"#;
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.names, ["Tom & Jerry", "émile"]);
+    }
+
+    #[test]
+    fn malformed_xml_is_an_error() {
+        assert!(parse("<graphml><graph><node id=\"a></graph></graphml>").is_err());
+        assert!(parse("<graphml><graph><node/></graph></graphml>").is_err());
+    }
+}
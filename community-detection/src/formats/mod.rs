@@ -0,0 +1,125 @@
+use crate::error::{RecordError, RecordErrorKind, Result};
+use petgraph::{EdgeType, Graph};
+use std::collections::HashMap;
+use std::path::Path;
+
+pub mod edge_list;
+pub mod gexf;
+pub mod gml;
+pub mod graphml;
+pub mod pajek;
+pub(crate) mod xml;
+
+// File formats `CommunityDetector::load_file` can read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphFormat {
+    // Delimited text as described by `CsvOptions`
+    Csv,
+    // Whitespace separated `source target [weight]` lines, as used by SNAP
+    // and KONECT. Lines starting with `#` or `%` are comments.
+    EdgeList,
+    GraphMl,
+    Gml,
+    // Pajek `.net` files
+    Pajek,
+}
+
+impl GraphFormat {
+    // Guess the format from the file extension, looking through a trailing
+    // `.gz` or `.zst`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        let name = [".gz", ".gzip", ".zst", ".zstd"]
+            .iter()
+            .find_map(|suffix| name.strip_suffix(suffix))
+            .unwrap_or(&name);
+        match name.rsplit_once('.')?.1 {
+            "csv" => Some(GraphFormat::Csv),
+            "txt" | "tsv" | "edges" | "edgelist" | "el" => Some(GraphFormat::EdgeList),
+            "graphml" => Some(GraphFormat::GraphMl),
+            "gml" => Some(GraphFormat::Gml),
+            "net" | "pajek" => Some(GraphFormat::Pajek),
+            _ => None,
+        }
+    }
+}
+
+// An edge as read from a graph file, before its weight is parsed
+#[derive(Debug)]
+pub(crate) struct ImportedEdge {
+    pub source: usize,
+    pub target: usize,
+    // `None` when the file gives no weight
+    pub weight: Option<String>,
+    pub directed: bool,
+    pub line: u64,
+}
+
+// Contents of a graph file in any format other than CSV. Names are unique
+// and include nodes without edges.
+#[derive(Debug, Default)]
+pub(crate) struct ImportedGraph {
+    pub names: Vec<String>,
+    pub edges: Vec<ImportedEdge>,
+    // Edges that could not be read, e.g. because they refer to an unknown node
+    pub bad_rows: Vec<RecordError>,
+    ids: HashMap<String, usize>,
+}
+
+impl ImportedGraph {
+    // Index of the node called `name`, added if it is new
+    pub fn node(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.ids.insert(name.to_string(), id);
+        id
+    }
+
+    // Edges as `(source, target, weight, directed)` with node names
+    #[cfg(test)]
+    fn edges_by_name(&self) -> Vec<(&str, &str, Option<&str>, bool)> {
+        self.edges
+            .iter()
+            .map(|edge| {
+                (
+                    self.names[edge.source].as_str(),
+                    self.names[edge.target].as_str(),
+                    edge.weight.as_deref(),
+                    edge.directed,
+                )
+            })
+            .collect()
+    }
+
+    #[cfg(test)]
+    fn bad_lines(&self) -> Vec<u64> {
+        self.bad_rows.iter().map(|error| error.line).collect()
+    }
+}
+
+pub(crate) fn parse(format: GraphFormat, text: &str) -> Result<ImportedGraph> {
+    match format {
+        GraphFormat::Csv => unreachable!("CSV goes through the ingest pipeline"),
+        GraphFormat::EdgeList => Ok(edge_list::parse(text)),
+        GraphFormat::GraphMl => graphml::parse(text),
+        GraphFormat::Gml => gml::parse(text),
+        GraphFormat::Pajek => pajek::parse(text),
+    }
+}
+
+fn malformed(line: u64, message: impl Into<String>) -> RecordError {
+    RecordError::new(line, RecordErrorKind::Malformed(message.into()))
+}
+
+// Number of edge ends at every node, counting self-loops twice
+pub(crate) fn degrees<W, Ty: EdgeType>(graph: &Graph<String, W, Ty>) -> Vec<usize> {
+    let mut degrees = vec![0; graph.node_count()];
+    for edge in graph.raw_edges() {
+        degrees[edge.source().index()] += 1;
+        degrees[edge.target().index()] += 1;
+    }
+    degrees
+}
@@ -0,0 +1,209 @@
+use super::{ImportedEdge, ImportedGraph, malformed};
+use crate::error::{Error, Result};
+
+enum Section {
+    None,
+    Vertices,
+    // `*Arcs` or `*Edges`, with whether they are directed
+    Pairs(bool),
+    // `*Arcslist` or `*Edgeslist`
+    Lists(bool),
+}
+
+// Vertices are named by their label, or by their number when they have none.
+// `*Arcs` and `*Arcslist` are directed, `*Edges` and `*Edgeslist` are not.
+// `*Matrix` sections are not supported.
+pub(crate) fn parse(text: &str) -> Result<ImportedGraph> {
+    let mut graph = ImportedGraph::default();
+    let mut section = Section::None;
+    // Labels by vertex number while `*Vertices` is read, then node indices
+    let mut labels: Vec<Option<String>> = Vec::new();
+    let mut nodes: Vec<usize> = Vec::new();
+
+    for (index, row) in text.lines().enumerate() {
+        let line = index as u64 + 1;
+        let row = row.trim();
+        if row.is_empty() || row.starts_with('%') {
+            continue;
+        }
+
+        if let Some(header) = row.strip_prefix('*') {
+            if matches!(section, Section::Vertices) {
+                nodes = add_vertices(&mut graph, &labels);
+            }
+            let keyword = header.split_whitespace().next().unwrap_or("");
+            section = match keyword.to_ascii_lowercase().as_str() {
+                "vertices" => {
+                    let count: usize = header
+                        .split_whitespace()
+                        .nth(1)
+                        .and_then(|count| count.parse().ok())
+                        .ok_or_else(|| malformed(line, "*Vertices without a count"))?;
+                    // Every vertex becomes a node even when it is not listed,
+                    // so a count beyond one vertex per input byte is taken as
+                    // corrupt rather than allocated.
+                    if count > text.len() {
+                        return Err(malformed(line, "*Vertices count exceeds the input").into());
+                    }
+                    labels = vec![None; count];
+                    Section::Vertices
+                }
+                "arcs" => Section::Pairs(true),
+                "edges" => Section::Pairs(false),
+                "arcslist" => Section::Lists(true),
+                "edgeslist" => Section::Lists(false),
+                _ => {
+                    return Err(Error::Schema(format!(
+                        "unsupported Pajek section *{} on line {}",
+                        keyword, line
+                    )));
+                }
+            };
+            continue;
+        }
+
+        let (first, rest) = row.split_once(char::is_whitespace).unwrap_or((row, ""));
+        let vertex = |id: &str| {
+            id.parse::<usize>()
+                .ok()
+                .and_then(|id| id.checked_sub(1))
+                .filter(|&id| id < labels.len())
+        };
+        match section {
+            Section::None => return Err(malformed(line, "data before the first section").into()),
+            Section::Vertices => {
+                let id = vertex(first).ok_or_else(|| malformed(line, "invalid vertex number"))?;
+                labels[id] = parse_label(rest.trim_start());
+            }
+            Section::Pairs(directed) => {
+                let mut fields = rest.split_whitespace();
+                match (vertex(first), fields.next().and_then(vertex)) {
+                    (Some(source), Some(target)) => graph.edges.push(ImportedEdge {
+                        source: nodes[source],
+                        target: nodes[target],
+                        weight: fields.next().map(str::to_string),
+                        directed,
+                        line,
+                    }),
+                    _ => graph.bad_rows.push(malformed(line, "unknown vertex")),
+                }
+            }
+            Section::Lists(directed) => {
+                let Some(source) = vertex(first) else {
+                    graph.bad_rows.push(malformed(line, "unknown vertex"));
+                    continue;
+                };
+                for target in rest.split_whitespace() {
+                    match vertex(target) {
+                        Some(target) => graph.edges.push(ImportedEdge {
+                            source: nodes[source],
+                            target: nodes[target],
+                            weight: None,
+                            directed,
+                            line,
+                        }),
+                        None => graph.bad_rows.push(malformed(line, "unknown vertex")),
+                    }
+                }
+            }
+        }
+    }
+
+    if matches!(section, Section::Vertices) {
+        add_vertices(&mut graph, &labels);
+    }
+    Ok(graph)
+}
+
+fn add_vertices(graph: &mut ImportedGraph, labels: &[Option<String>]) -> Vec<usize> {
+    labels
+        .iter()
+        .enumerate()
+        .map(|(id, label)| match label {
+            Some(label) => graph.node(label),
+            None => graph.node(&(id + 1).to_string()),
+        })
+        .collect()
+}
+
+// The quoted or bare label at the start of a vertex line, before any
+// coordinates or shape attributes
+fn parse_label(rest: &str) -> Option<String> {
+    let label = match rest.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next()?,
+        None => rest.split_whitespace().next()?,
+    };
+    Some(label.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertices_are_named_by_label_or_number() {
+        let text =
+            "% a comment\n*Vertices 3\n1 \"Ann Lee\" 0.1 0.2 ic Red\n2 bob\n*Arcs\n1 2 3\n3 1\n";
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.names, ["Ann Lee", "bob", "3"]);
+        assert_eq!(
+            graph.edges_by_name(),
+            [
+                ("Ann Lee", "bob", Some("3"), true),
+                ("3", "Ann Lee", None, true)
+            ]
+        );
+        assert_eq!(graph.edges[1].line, 7);
+    }
+
+    #[test]
+    fn edges_are_undirected() {
+        let text = "*vertices 2\n*Edges\n1 2 0.5\n*Arcs\n2 1\n";
+        assert_eq!(
+            parse(text).unwrap().edges_by_name(),
+            [("1", "2", Some("0.5"), false), ("2", "1", None, true)]
+        );
+    }
+
+    #[test]
+    fn adjacency_lists() {
+        let text = "*Vertices 3\n*Arcslist\n1 2 3\n*Edgeslist\n2 3\n";
+        assert_eq!(
+            parse(text).unwrap().edges_by_name(),
+            [
+                ("1", "2", None, true),
+                ("1", "3", None, true),
+                ("2", "3", None, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_vertices_are_bad() {
+        let text = "*Vertices 2\n*Arcs\n1 2\n1 5\n0 1\n*Arcslist\n9 1\n2 1 4\n";
+        let graph = parse(text).unwrap();
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.bad_lines(), [4, 5, 7, 8]);
+    }
+
+    #[test]
+    fn structural_errors() {
+        assert!(matches!(parse("1 2\n"), Err(Error::Record(_))));
+        assert!(matches!(parse("*Vertices\n"), Err(Error::Record(_))));
+        assert!(matches!(parse("*Vertices 2\n3 c\n"), Err(Error::Record(_))));
+        assert!(matches!(
+            parse("*Vertices 2\n*Matrix\n0 1\n"),
+            Err(Error::Schema(_))
+        ));
+    }
+
+    #[test]
+    fn vertex_count_must_fit_the_input() {
+        match parse("*Vertices 99999999999999\n1 a\n") {
+            Err(Error::Record(error)) => assert_eq!(error.line, 1),
+            _ => panic!("expected a malformed record"),
+        }
+        let graph = parse("*Vertices 20\n*Edges\n1 2\n").unwrap();
+        assert_eq!(graph.names.len(), 20);
+    }
+}
@@ -0,0 +1,289 @@
+use super::malformed;
+use crate::error::RecordError;
+
+// Just enough XML to read GraphML: elements, attributes, text and the
+// predefined and numeric entities. Comments, processing instructions,
+// doctypes and CDATA sections are skipped or passed through as text.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Event<'a> {
+    Start {
+        name: &'a str,
+        attributes: Vec<(&'a str, String)>,
+        // `<name/>`, which has no matching `End`
+        empty: bool,
+        line: u64,
+    },
+    End {
+        name: &'a str,
+    },
+    Text(String),
+}
+
+pub(crate) struct Reader<'a> {
+    text: &'a str,
+    position: usize,
+    line: u64,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Reader {
+            text,
+            position: 0,
+            line: 1,
+        }
+    }
+
+    // Move past `len` bytes, counting lines on the way
+    fn advance(&mut self, len: usize) -> &'a str {
+        let skipped = &self.text[self.position..self.position + len];
+        self.line += skipped.bytes().filter(|&byte| byte == b'\n').count() as u64;
+        self.position += len;
+        skipped
+    }
+
+    // Skip to just after `terminator`
+    fn skip_past(&mut self, terminator: &str) -> Result<(), RecordError> {
+        match self.text[self.position..].find(terminator) {
+            Some(offset) => {
+                self.advance(offset + terminator.len());
+                Ok(())
+            }
+            None => Err(malformed(self.line, format!("missing {:?}", terminator))),
+        }
+    }
+
+    pub fn next_event(&mut self) -> Result<Option<Event<'a>>, RecordError> {
+        loop {
+            let rest = &self.text[self.position..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+
+            if !rest.starts_with('<') {
+                let len = rest.find('<').unwrap_or(rest.len());
+                let line = self.line;
+                let text = self.advance(len);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                return unescape(text, line).map(|text| Some(Event::Text(text)));
+            }
+
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let Some(len) = cdata.find("]]>") else {
+                    return Err(malformed(self.line, "unterminated CDATA section"));
+                };
+                let text = cdata[..len].to_string();
+                self.advance("<![CDATA[".len() + len + "]]>".len());
+                return Ok(Some(Event::Text(text)));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else if let Some(end) = rest.strip_prefix("</") {
+                let Some(len) = end.find('>') else {
+                    return Err(malformed(self.line, "unterminated end tag"));
+                };
+                let name = end[..len].trim();
+                self.advance(2 + len + 1);
+                return Ok(Some(Event::End { name }));
+            } else {
+                return self.start_tag().map(Some);
+            }
+        }
+    }
+
+    fn start_tag(&mut self) -> Result<Event<'a>, RecordError> {
+        let line = self.line;
+        let rest = &self.text[self.position + 1..];
+        let len = tag_length(rest).ok_or_else(|| malformed(line, "unterminated tag"))?;
+        let tag = &rest[..len];
+        self.advance(1 + len + 1);
+
+        let (tag, empty) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let name_len = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let name = &tag[..name_len];
+        if name.is_empty() {
+            return Err(malformed(line, "tag without a name"));
+        }
+
+        let mut attributes = Vec::new();
+        let mut rest = tag[name_len..].trim_start();
+        while !rest.is_empty() {
+            let invalid = || malformed(line, format!("invalid attribute in <{}>", name));
+            let (key, value) = rest.split_once('=').ok_or_else(invalid)?;
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'');
+            let quote = quote.ok_or_else(invalid)?;
+            let value = &value[1..];
+            let end = value.find(quote).ok_or_else(invalid)?;
+            attributes.push((key.trim(), unescape(&value[..end], line)?));
+            rest = value[end + 1..].trim_start();
+        }
+
+        Ok(Event::Start {
+            name,
+            attributes,
+            empty,
+            line,
+        })
+    }
+}
+
+// Length of a tag up to its closing `>`, skipping any `>` inside quotes
+fn tag_length(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Name without its namespace prefix, e.g. `node` for `gexf:node`
+pub(crate) fn local_name(name: &str) -> &str {
+    name.rsplit_once(':').map_or(name, |(_, local)| local)
+}
+
+pub(crate) fn attribute<'e>(attributes: &'e [(&str, String)], key: &str) -> Option<&'e str> {
+    attributes
+        .iter()
+        .find(|(name, _)| local_name(name) == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn unescape(text: &str, line: u64) -> Result<String, RecordError> {
+    if !text.contains('&') {
+        return Ok(text.to_string());
+    }
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find(';')
+            .ok_or_else(|| malformed(line, "unterminated entity"))?;
+        let entity = &rest[start + 1..start + end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        };
+        unescaped.push(c.ok_or_else(|| malformed(line, format!("unknown entity &{};", entity)))?);
+        rest = &rest[start + end + 1..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+// Escape text for element content and double-quoted attribute values
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\t' => escaped.push_str("&#9;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(text: &str) -> Vec<Event<'_>> {
+        let mut reader = Reader::new(text);
+        let mut events = Vec::new();
+        while let Some(event) = reader.next_event().unwrap() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn elements_attributes_and_text() {
+        let text = "<?xml version=\"1.0\"?>\n<!DOCTYPE g>\n<!-- a <comment> -->\n<g:a k='v' x = \"1 > 0\">\n  hi\n<b/></g:a>";
+        assert_eq!(
+            events(text),
+            [
+                Event::Start {
+                    name: "g:a",
+                    attributes: vec![("k", "v".to_string()), ("x", "1 > 0".to_string())],
+                    empty: false,
+                    line: 4,
+                },
+                Event::Text("\n  hi\n".to_string()),
+                Event::Start {
+                    name: "b",
+                    attributes: Vec::new(),
+                    empty: true,
+                    line: 6,
+                },
+                Event::End { name: "g:a" },
+            ]
+        );
+    }
+
+    #[test]
+    fn entities_are_decoded() {
+        let text = "<a t=\"&quot;&amp;&apos;\">&lt;&#65;&#x42;&gt;</a>";
+        let events = events(text);
+        assert!(matches!(&events[0], Event::Start { attributes, .. } if attributes[0].1 == "\"&'"));
+        assert_eq!(events[1], Event::Text("<AB>".to_string()));
+    }
+
+    #[test]
+    fn cdata_is_passed_through() {
+        let events = events("<a><![CDATA[x < y & z]]></a>");
+        assert_eq!(events[1], Event::Text("x < y & z".to_string()));
+    }
+
+    #[test]
+    fn unknown_entity_is_an_error() {
+        let mut reader = Reader::new("<a>\n&nbsp;</a>");
+        reader.next_event().unwrap();
+        let error = reader.next_event().unwrap_err();
+        assert_eq!(error.line, 1);
+        assert!(matches!(
+            error.kind,
+            crate::error::RecordErrorKind::Malformed(_)
+        ));
+    }
+
+    #[test]
+    fn unterminated_tag_is_an_error() {
+        let mut reader = Reader::new("\n<a b=\"c\"");
+        assert_eq!(reader.next_event().unwrap_err().line, 2);
+    }
+
+    #[test]
+    fn escape_round_trips() {
+        let name = "a<b>&\"c\"\td";
+        assert_eq!(unescape(&escape(name), 1).unwrap(), name);
+        assert_eq!(local_name("gexf:node"), "node");
+    }
+}
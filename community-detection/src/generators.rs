@@ -0,0 +1,462 @@
+use crate::{INTERACTION_HEADER, INTERACTION_STREAM, UsernameGenerator, chunk_rng};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Write};
+
+// Distribution of the weight written for each generated interaction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WeightDistribution {
+    Constant(u32),
+    // Uniform over `min..=max`
+    Uniform { min: u32, max: u32 },
+    // 1 + a geometric variable, so the mean weight is `mean` (at least 1)
+    Geometric { mean: f64 },
+}
+
+impl WeightDistribution {
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> u32 {
+        match *self {
+            WeightDistribution::Constant(weight) => weight,
+            WeightDistribution::Uniform { min, max } => rng.gen_range(min..=max),
+            WeightDistribution::Geometric { mean } => {
+                if mean <= 1.0 {
+                    return 1;
+                }
+                let success = 1.0 / mean;
+                let uniform: f64 = rng.r#gen();
+                1 + ((1.0 - uniform).ln() / (1.0 - success).ln()).floor() as u32
+            }
+        }
+    }
+
+    fn validate(&self, name: &str) -> std::io::Result<()> {
+        match *self {
+            WeightDistribution::Uniform { min, max } if min > max => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{}: Uniform min must not exceed max", name),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Planted partition / stochastic block model: users are split into
+// `num_blocks` equally sized blocks and every pair of users interacts with
+// probability `intra_probability` inside a block and `inter_probability`
+// across blocks.
+#[derive(Clone, Debug)]
+pub struct PlantedPartitionConfig {
+    pub num_blocks: usize,
+    pub users_per_block: usize,
+    pub intra_probability: f64,
+    pub inter_probability: f64,
+    pub intra_weights: WeightDistribution,
+    pub inter_weights: WeightDistribution,
+    // Fixed seed for reproducible output, random when `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for PlantedPartitionConfig {
+    fn default() -> Self {
+        PlantedPartitionConfig {
+            num_blocks: 4,
+            users_per_block: 25,
+            intra_probability: 0.3,
+            inter_probability: 0.02,
+            intra_weights: WeightDistribution::Uniform { min: 5, max: 20 },
+            inter_weights: WeightDistribution::Uniform { min: 1, max: 5 },
+            seed: None,
+        }
+    }
+}
+
+impl PlantedPartitionConfig {
+    fn validate(&self) -> std::io::Result<()> {
+        let invalid = |reason: &str| Err(Error::new(ErrorKind::InvalidInput, reason.to_string()));
+        if !(0.0..=1.0).contains(&self.intra_probability) {
+            return invalid("intra_probability must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&self.inter_probability) {
+            return invalid("inter_probability must be between 0 and 1");
+        }
+        self.intra_weights.validate("intra_weights")?;
+        self.inter_weights.validate("inter_weights")
+    }
+}
+
+// Write a planted partition graph as `source,target,weight` rows to `filename`
+// and the block of every user as `user,community` rows to `ground_truth_filename`.
+pub fn generate_planted_partition_csv(
+    config: &PlantedPartitionConfig,
+    filename: &str,
+    ground_truth_filename: &str,
+) -> std::io::Result<()> {
+    config.validate()?;
+    let seed = config.seed.unwrap_or_else(|| thread_rng().r#gen());
+    let generator = UsernameGenerator::new();
+    let users =
+        generator.generate_unique_batch_seeded(config.num_blocks * config.users_per_block, seed)?;
+    let block_of = |user: usize| user * config.num_blocks / users.len();
+
+    write_ground_truth(
+        ground_truth_filename,
+        users
+            .iter()
+            .enumerate()
+            .map(|(i, user)| (user.as_str(), block_of(i))),
+    )?;
+
+    // One generator per source user keeps the output independent of scheduling.
+    let rows: Vec<String> = (0..users.len())
+        .into_par_iter()
+        .map(|a| {
+            let mut rng = chunk_rng(seed, INTERACTION_STREAM, a);
+            let mut rows = String::new();
+            for b in a + 1..users.len() {
+                let (probability, weights) = if block_of(a) == block_of(b) {
+                    (config.intra_probability, &config.intra_weights)
+                } else {
+                    (config.inter_probability, &config.inter_weights)
+                };
+                if rng.gen_bool(probability) {
+                    let weight = weights.sample(&mut rng);
+                    rows.push_str(&format!("{},{},{}\n", users[a], users[b], weight));
+                }
+            }
+            rows
+        })
+        .collect();
+
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "{}", INTERACTION_HEADER)?;
+    for row in rows {
+        writer.write_all(row.as_bytes())?;
+    }
+    writer.flush()
+}
+
+// LFR benchmark (Lancichinetti, Fortunato & Radicchi, 2008): node degrees
+// and community sizes follow power laws, and every node shares a fraction
+// `mixing` of its edges with nodes outside its own community.
+#[derive(Clone, Debug)]
+pub struct LfrConfig {
+    pub num_users: usize,
+    pub average_degree: f64,
+    pub max_degree: usize,
+    // Exponent of the degree distribution, typically in [2, 3]
+    pub degree_exponent: f64,
+    // Exponent of the community size distribution, typically in [1, 2]
+    pub community_exponent: f64,
+    // Fraction of each node's edges that leave its community (mu)
+    pub mixing: f64,
+    pub min_community: usize,
+    pub max_community: usize,
+    pub weights: WeightDistribution,
+    // Fixed seed for reproducible output, random when `None`
+    pub seed: Option<u64>,
+}
+
+impl Default for LfrConfig {
+    fn default() -> Self {
+        LfrConfig {
+            num_users: 1000,
+            average_degree: 15.0,
+            max_degree: 50,
+            degree_exponent: 2.5,
+            community_exponent: 1.5,
+            mixing: 0.1,
+            min_community: 20,
+            max_community: 100,
+            weights: WeightDistribution::Uniform { min: 1, max: 20 },
+            seed: None,
+        }
+    }
+}
+
+impl LfrConfig {
+    fn validate(&self) -> std::io::Result<()> {
+        let invalid = |reason: &str| Err(Error::new(ErrorKind::InvalidInput, reason.to_string()));
+        if !(0.0..=1.0).contains(&self.mixing) {
+            return invalid("mixing must be between 0 and 1");
+        }
+        if self.average_degree < 1.0 || self.average_degree > self.max_degree as f64 {
+            return invalid("average_degree must be between 1 and max_degree");
+        }
+        if self.max_degree >= self.num_users {
+            return invalid("max_degree must be smaller than num_users");
+        }
+        if self.min_community < 1 || self.min_community > self.max_community {
+            return invalid("min_community must be between 1 and max_community");
+        }
+        if self.max_community > self.num_users {
+            return invalid("max_community must not exceed num_users");
+        }
+        self.weights.validate("weights")
+    }
+}
+
+// Write an LFR benchmark graph as `source,target,weight` rows to `filename` and
+// the planted communities as `user,community` rows to `ground_truth_filename`.
+pub fn generate_lfr_csv(
+    config: &LfrConfig,
+    filename: &str,
+    ground_truth_filename: &str,
+) -> std::io::Result<()> {
+    config.validate()?;
+    let seed = config.seed.unwrap_or_else(|| thread_rng().r#gen());
+    let mut rng = chunk_rng(seed, INTERACTION_STREAM, 0);
+
+    let users = UsernameGenerator::new().generate_unique_batch_seeded(config.num_users, seed)?;
+    let n = users.len();
+
+    // Degrees: pick the lower cut-off so the power law has the requested mean.
+    let min_degree = power_law_minimum(
+        config.average_degree,
+        config.max_degree as f64,
+        config.degree_exponent,
+    );
+    let degrees: Vec<usize> = (0..n)
+        .map(|_| {
+            sample_power_law(
+                &mut rng,
+                min_degree,
+                config.max_degree as f64,
+                config.degree_exponent,
+            )
+            .round() as usize
+        })
+        .collect();
+
+    let sizes = community_sizes(&mut rng, config)?;
+
+    // Assign users to communities, largest internal degree first, so that
+    // every user fits in a community with room for its internal links.
+    let mut internal: Vec<usize> = degrees
+        .iter()
+        .map(|&degree| ((1.0 - config.mixing) * degree as f64).round() as usize)
+        .collect();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.shuffle(&mut rng);
+    order.sort_by_key(|&user| std::cmp::Reverse(internal[user]));
+
+    let mut free = sizes.clone();
+    let mut community_of = vec![0; n];
+    for &user in &order {
+        let fitting: Vec<usize> = (0..sizes.len())
+            .filter(|&c| free[c] > 0 && sizes[c] > internal[user])
+            .collect();
+        let community = match fitting.choose(&mut rng) {
+            Some(&community) => community,
+            None => {
+                let open: Vec<usize> = (0..sizes.len()).filter(|&c| free[c] > 0).collect();
+                let community = *open.choose(&mut rng).unwrap();
+                internal[user] = sizes[community] - 1;
+                community
+            }
+        };
+        community_of[user] = community;
+        free[community] -= 1;
+    }
+
+    // Wire internal stubs within each community, then external stubs across
+    // communities.
+    let mut edges = HashSet::new();
+    let mut members = vec![Vec::new(); sizes.len()];
+    for user in 0..n {
+        members[community_of[user]].push(user);
+    }
+    for community in &members {
+        let stubs: Vec<usize> = community
+            .iter()
+            .flat_map(|&user| std::iter::repeat_n(user, internal[user]))
+            .collect();
+        wire_stubs(&mut rng, stubs, &mut edges, |_, _| true);
+    }
+
+    let stubs: Vec<usize> = (0..n)
+        .flat_map(|user| std::iter::repeat_n(user, degrees[user].saturating_sub(internal[user])))
+        .collect();
+    wire_stubs(&mut rng, stubs, &mut edges, |a, b| {
+        community_of[a] != community_of[b]
+    });
+
+    write_ground_truth(
+        ground_truth_filename,
+        users
+            .iter()
+            .zip(&community_of)
+            .map(|(user, &community)| (user.as_str(), community)),
+    )?;
+
+    let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
+    edges.sort_unstable();
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "{}", INTERACTION_HEADER)?;
+    for (a, b) in edges {
+        let weight = config.weights.sample(&mut rng);
+        writeln!(writer, "{},{},{}", users[a], users[b], weight)?;
+    }
+    writer.flush()
+}
+
+// Community sizes between `min_community` and `max_community` that add up to
+// `num_users`. Sizes are drawn until every user has a place; when trimming the
+// last community would take it below `min_community`, its users are spread
+// over the other communities instead.
+fn community_sizes<R: Rng + ?Sized>(
+    rng: &mut R,
+    config: &LfrConfig,
+) -> std::io::Result<Vec<usize>> {
+    let n = config.num_users;
+    for _ in 0..1000 {
+        let mut sizes = Vec::new();
+        let mut total = 0;
+        while total < n {
+            let size = sample_power_law(
+                rng,
+                config.min_community as f64,
+                config.max_community as f64,
+                config.community_exponent,
+            )
+            .round() as usize;
+            sizes.push(size);
+            total += size;
+        }
+
+        let last = sizes.pop().unwrap();
+        let remainder = last - (total - n);
+        if remainder >= config.min_community {
+            sizes.push(remainder);
+            return Ok(sizes);
+        }
+        let room: usize = sizes.iter().map(|&size| config.max_community - size).sum();
+        if room < remainder {
+            continue;
+        }
+        for _ in 0..remainder {
+            let open: Vec<usize> = (0..sizes.len())
+                .filter(|&c| sizes[c] < config.max_community)
+                .collect();
+            sizes[*open.choose(rng).unwrap()] += 1;
+        }
+        return Ok(sizes);
+    }
+    Err(Error::new(
+        ErrorKind::InvalidInput,
+        "num_users cannot be split into communities between min_community and max_community",
+    ))
+}
+
+// Pair up `stubs` at random into new edges between nodes that `allowed`
+// accepts. Pairs that would form a self-loop, a repeated edge or a disallowed
+// edge are shuffled and paired again. Stubs that still have no partner are
+// joined by rewiring: an edge `c-d` wired here becomes `a-c` and `b-d`, which
+// keeps the degrees of `c` and `d`. Only stubs that fail both are dropped.
+fn wire_stubs<R: Rng + ?Sized>(
+    rng: &mut R,
+    mut stubs: Vec<usize>,
+    edges: &mut HashSet<(usize, usize)>,
+    allowed: impl Fn(usize, usize) -> bool,
+) {
+    const WIRING_ROUNDS: usize = 20;
+    const REWIRING_ATTEMPTS: usize = 100;
+    let key = |a: usize, b: usize| (a.min(b), a.max(b));
+    let valid = |edges: &HashSet<_>, a: usize, b: usize| {
+        a != b && allowed(a, b) && !edges.contains(&key(a, b))
+    };
+
+    let mut wired = Vec::new();
+    for _ in 0..WIRING_ROUNDS {
+        if stubs.len() < 2 {
+            break;
+        }
+        stubs.shuffle(rng);
+        let mut unpaired = Vec::new();
+        for pair in stubs.chunks(2) {
+            match *pair {
+                [a, b] if valid(edges, a, b) => {
+                    edges.insert(key(a, b));
+                    wired.push((a, b));
+                }
+                _ => unpaired.extend_from_slice(pair),
+            }
+        }
+        stubs = unpaired;
+    }
+
+    while let (Some(a), Some(b)) = (stubs.pop(), stubs.pop()) {
+        if wired.is_empty() {
+            return;
+        }
+        for _ in 0..REWIRING_ATTEMPTS {
+            let index = rng.gen_range(0..wired.len());
+            let (c, d) = if rng.r#gen() {
+                wired[index]
+            } else {
+                (wired[index].1, wired[index].0)
+            };
+            if valid(edges, a, c) && valid(edges, b, d) && key(a, c) != key(b, d) {
+                edges.remove(&key(c, d));
+                edges.insert(key(a, c));
+                edges.insert(key(b, d));
+                wired[index] = (a, c);
+                wired.push((b, d));
+                break;
+            }
+        }
+    }
+}
+
+// Continuous power law on `[min, max]` with density proportional to x^-exponent.
+fn sample_power_law<R: Rng + ?Sized>(rng: &mut R, min: f64, max: f64, exponent: f64) -> f64 {
+    let uniform: f64 = rng.r#gen();
+    if (exponent - 1.0).abs() < 1e-9 {
+        return min * (max / min).powf(uniform);
+    }
+    let power = 1.0 - exponent;
+    let low = min.powf(power);
+    let high = max.powf(power);
+    (low + (high - low) * uniform).powf(1.0 / power)
+}
+
+fn power_law_mean(min: f64, max: f64, exponent: f64) -> f64 {
+    let moment = |k: f64| {
+        if k.abs() < 1e-9 {
+            (max / min).ln()
+        } else {
+            (max.powf(k) - min.powf(k)) / k
+        }
+    };
+    moment(2.0 - exponent) / moment(1.0 - exponent)
+}
+
+// Lower cut-off of a power law on `[min, max]` whose mean is `mean`, by bisection.
+fn power_law_minimum(mean: f64, max: f64, exponent: f64) -> f64 {
+    let (mut low, mut high) = (1.0, max);
+    for _ in 0..100 {
+        let mid = (low + high) / 2.0;
+        if power_law_mean(mid, max, exponent) < mean {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+// Ground truth in the `user,community` format read by `load_partition_csv`.
+fn write_ground_truth<'a>(
+    filename: &str,
+    assignments: impl Iterator<Item = (&'a str, usize)>,
+) -> std::io::Result<()> {
+    let mut writer = BufWriter::new(File::create(filename)?);
+    writeln!(writer, "user,community")?;
+    for (user, community) in assignments {
+        writeln!(writer, "{},{}", user, community)?;
+    }
+    writer.flush()
+}
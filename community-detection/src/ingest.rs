@@ -0,0 +1,1134 @@
+use crate::INTERACTION_HEADER;
+use crate::aggregation::{Accumulator, Aggregation, OverflowPolicy};
+use crate::error::{Error, RecordError, RecordErrorKind, Result};
+use crate::formats::ImportedGraph;
+use crate::weight::EdgeWeight;
+use csv::{ReaderBuilder, StringRecord};
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::{EdgeType, Graph};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, RandomState};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// Smallest slice of input handed to a single parser
+const MIN_CHUNK_BYTES: usize = 1 << 20;
+// Input read from a stream at once and split into chunks
+pub(crate) const BLOCK_BYTES: usize = 64 << 20;
+
+// What to do with input rows that cannot be turned into an edge.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BadRowPolicy {
+    // Stop loading and return the first bad row as an error
+    #[default]
+    Fail,
+    // Ignore bad rows
+    Skip,
+    // Ignore bad rows, but return them alongside the graph
+    Collect,
+}
+
+// A CSV column, either by 0-based position or by header name.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Column {
+    Index(usize),
+    Name(String),
+}
+
+// How the weight column is written. Only integer edge weights look at this;
+// float edge weights always accept decimals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeightFormat {
+    #[default]
+    Integer,
+    // Decimal weights, rounded to the nearest integer for integer edge weights
+    Float,
+}
+
+// What to do with rows whose source and target are the same user.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelfLoopPolicy {
+    #[default]
+    Keep,
+    Drop,
+}
+
+// Options for loading interaction CSVs. The defaults read the
+// `source,target,weight` files written by `generate_interaction_csv`.
+#[derive(Clone, Debug)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    pub has_headers: bool,
+    pub source: Column,
+    pub target: Column,
+    // `None` gives every edge `default_weight`
+    pub weight: Option<Column>,
+    // Used when there is no weight column or the weight field is empty
+    pub default_weight: f64,
+    pub weight_format: WeightFormat,
+    // How repeated pairs are combined into one edge
+    pub aggregation: Aggregation,
+    pub overflow: OverflowPolicy,
+    pub self_loops: SelfLoopPolicy,
+    pub bad_rows: BadRowPolicy,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',',
+            has_headers: true,
+            source: Column::Index(0),
+            target: Column::Index(1),
+            weight: Some(Column::Index(2)),
+            default_weight: 1.0,
+            weight_format: WeightFormat::Integer,
+            aggregation: Aggregation::Sum,
+            overflow: OverflowPolicy::Saturate,
+            self_loops: SelfLoopPolicy::Keep,
+            bad_rows: BadRowPolicy::Fail,
+        }
+    }
+}
+
+impl CsvOptions {
+    fn reader(&self, has_headers: bool) -> ReaderBuilder {
+        let mut builder = ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .has_headers(has_headers)
+            .flexible(true);
+        builder
+    }
+
+    // Resolve column names against the header row of `bytes`.
+    fn schema<W: EdgeWeight>(&self, bytes: &[u8]) -> Result<Schema<W>> {
+        let headers = if self.has_headers {
+            let mut reader = self.reader(true).from_reader(bytes);
+            Some(
+                reader
+                    .headers()
+                    .map_err(|error| Error::Schema(error.to_string()))?
+                    .clone(),
+            )
+        } else {
+            None
+        };
+
+        let resolve = |column: &Column| match (column, &headers) {
+            (Column::Index(index), _) => Ok(*index),
+            (Column::Name(name), Some(headers)) => headers
+                .iter()
+                .position(|header| header.trim() == name)
+                .ok_or_else(|| Error::Schema(format!("column {:?} not found in header", name))),
+            (Column::Name(name), None) => Err(Error::Schema(format!(
+                "column {:?} is selected by name but the input has no header",
+                name
+            ))),
+        };
+
+        let schema = Schema {
+            source: resolve(&self.source)?,
+            target: resolve(&self.target)?,
+            weight: self.weight.as_ref().map(resolve).transpose()?,
+            default_weight: W::from_f64(self.default_weight).ok_or_else(|| {
+                Error::Schema(format!(
+                    "default weight {} is not a valid edge weight",
+                    self.default_weight
+                ))
+            })?,
+            weight_format: self.weight_format,
+        };
+        if let Some(headers) = headers.filter(|headers| !headers.is_empty()) {
+            validate_header(&headers, &schema)?;
+            if self.has_default_columns() {
+                validate_interaction_header(&headers)?;
+            }
+        }
+        Ok(schema)
+    }
+
+    fn has_default_columns(&self) -> bool {
+        let defaults = CsvOptions::default();
+        self.source == defaults.source
+            && self.target == defaults.target
+            && self.weight == defaults.weight
+    }
+}
+
+// With the default columns the header has to be `INTERACTION_HEADER`, so
+// that a file with its columns in another order is not read the wrong way
+// round. Columns after the weight are ignored.
+fn validate_interaction_header(headers: &StringRecord) -> Result<()> {
+    let expected = INTERACTION_HEADER.split(',');
+    let matches = headers.len() >= expected.clone().count()
+        && expected
+            .zip(headers)
+            .all(|(name, header)| header.trim() == name);
+    if matches {
+        return Ok(());
+    }
+    Err(Error::Schema(format!(
+        "header {:?} is not {:?}; select the columns by name for other headers, or set `has_headers: false` for input without a header",
+        headers.iter().collect::<Vec<_>>().join(","),
+        INTERACTION_HEADER
+    )))
+}
+
+// Catch a header row that is missing a selected column or that looks like an
+// interaction, which would otherwise be dropped without notice.
+fn validate_header<W>(headers: &StringRecord, schema: &Schema<W>) -> Result<()> {
+    let columns = [Some(schema.source), Some(schema.target), schema.weight];
+    if let Some(index) = columns
+        .into_iter()
+        .flatten()
+        .find(|&index| index >= headers.len())
+    {
+        return Err(Error::Schema(format!(
+            "header {:?} has no column {}",
+            headers.iter().collect::<Vec<_>>().join(","),
+            index
+        )));
+    }
+    if let Some(weight) = schema.weight
+        && headers[weight].trim().parse::<f64>().is_ok()
+    {
+        return Err(Error::Schema(format!(
+            "header {:?} looks like an interaction; set `has_headers: false` for input without a header",
+            headers.iter().collect::<Vec<_>>().join(",")
+        )));
+    }
+    Ok(())
+}
+
+// `CsvOptions` with columns resolved to indices
+struct Schema<W> {
+    source: usize,
+    target: usize,
+    weight: Option<usize>,
+    default_weight: W,
+    weight_format: WeightFormat,
+}
+
+impl<W: EdgeWeight> Schema<W> {
+    fn parse<'r>(
+        &self,
+        record: &'r StringRecord,
+    ) -> std::result::Result<(&'r str, &'r str, W), RecordErrorKind> {
+        let field = |index| {
+            record
+                .get(index)
+                .ok_or(RecordErrorKind::MissingColumn(index))
+        };
+        let user1 = field(self.source)?;
+        let user2 = field(self.target)?;
+
+        let weight = match self.weight {
+            None => self.default_weight,
+            Some(index) => parse_weight(field(index)?, self.weight_format, self.default_weight)?,
+        };
+
+        Ok((user1, user2, weight))
+    }
+}
+
+// An empty field gives `default_weight`.
+fn parse_weight<W: EdgeWeight>(
+    value: &str,
+    format: WeightFormat,
+    default_weight: W,
+) -> std::result::Result<W, RecordErrorKind> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Ok(default_weight);
+    }
+    let number = match format {
+        WeightFormat::Integer if !W::FLOAT => trimmed.parse::<u64>().map(|n| n as f64).ok(),
+        _ => trimmed.parse::<f64>().ok(),
+    };
+    number
+        .and_then(W::from_f64)
+        .ok_or_else(|| RecordErrorKind::InvalidWeight(value.to_string()))
+}
+
+// Edges of one chunk in global ids, grouped by shard
+type ShardedEdges<W> = Vec<Vec<(u32, u32, Accumulator<W>)>>;
+
+// Records parsed from one chunk of the input. Names are interned locally so
+// that the expensive parsing and hashing happens without any shared state.
+struct Chunk<W> {
+    names: Vec<String>,
+    // Shard of every local name, and local names grouped by shard
+    shard_of: Vec<u32>,
+    names_by_shard: Vec<Vec<u32>>,
+    edges: HashMap<(u32, u32), Accumulator<W>>,
+    bad_rows: Vec<RecordError>,
+}
+
+impl<W> Chunk<W> {
+    fn new(shard_count: usize) -> Self {
+        Chunk {
+            names: Vec::new(),
+            shard_of: Vec::new(),
+            names_by_shard: vec![Vec::new(); shard_count],
+            edges: HashMap::new(),
+            bad_rows: Vec::new(),
+        }
+    }
+}
+
+// Interactions parsed from one block of input, with block-local node ids.
+// Edges are not finished yet, so they can still be merged into edges that
+// are already in the graph.
+struct Block<W> {
+    names: Vec<String>,
+    edges: Vec<(u32, u32, Accumulator<W>)>,
+    bad_rows: Vec<RecordError>,
+}
+
+// Parse a block of CSV bytes laid out as described by `options`.
+//
+// The block is split at line boundaries and every chunk is parsed in
+// parallel. Node names are then deduplicated in parallel shards keyed by
+// their hash, and repeated pairs are aggregated as set by
+// `options.aggregation` in shards keyed by their endpoints. Nodes and edges
+// come out in a deterministic order: nodes by first appearance, edges by
+// endpoints. For undirected graphs `A,B` and `B,A` are aggregated into one
+// edge.
+//
+// Quoted fields containing line breaks are not supported, since chunks are
+// cut at raw newlines.
+//
+// Rows that cannot be parsed are handled according to `options.bad_rows`;
+// with `BadRowPolicy::Collect` they are returned in input order.
+fn parse_block<W: EdgeWeight, Ty: EdgeType>(
+    bytes: &[u8],
+    first_line: u64,
+    has_headers: bool,
+    options: &CsvOptions,
+    schema: &Schema<W>,
+) -> Result<Block<W>> {
+    let hasher = RandomState::new();
+    let shard_count = rayon::current_num_threads() * 4;
+
+    let slices = split_lines(bytes);
+    let line_counts: Vec<u64> = slices
+        .par_iter()
+        .map(|data| data.iter().filter(|&&byte| byte == b'\n').count() as u64)
+        .collect();
+    let first_lines = line_counts.iter().scan(first_line, |line, &count| {
+        let first = *line;
+        *line += count;
+        Some(first)
+    });
+
+    let parsed: Vec<Result<Chunk<W>>> = slices
+        .into_par_iter()
+        .zip(first_lines.collect::<Vec<_>>())
+        .enumerate()
+        .map(|(i, (data, first_line))| {
+            parse_chunk::<W, Ty>(
+                data,
+                first_line,
+                has_headers && i == 0,
+                options,
+                schema,
+                &hasher,
+                shard_count,
+            )
+        })
+        .collect();
+    // Report the earliest failure in the input, not whichever chunk failed first.
+    let mut chunks: Vec<Chunk<W>> = parsed.into_iter().collect::<Result<_>>()?;
+    let bad_rows: Vec<RecordError> = chunks
+        .iter_mut()
+        .flat_map(|chunk| std::mem::take(&mut chunk.bad_rows))
+        .collect();
+
+    // First occurrence (chunk, local id) of every distinct name, per shard
+    let shards: Vec<HashMap<&str, (u32, u32)>> = (0..shard_count)
+        .into_par_iter()
+        .map(|shard| {
+            let mut first = HashMap::new();
+            for (c, chunk) in chunks.iter().enumerate() {
+                for &local in &chunk.names_by_shard[shard] {
+                    first
+                        .entry(chunk.names[local as usize].as_str())
+                        .or_insert((c as u32, local));
+                }
+            }
+            first
+        })
+        .collect();
+
+    // Global node ids follow the order of first appearance in the input.
+    let mut order: Vec<(u32, u32)> = shards
+        .par_iter()
+        .flat_map_iter(|shard| shard.values().copied())
+        .collect();
+    order.par_sort_unstable();
+
+    let mappings: Vec<Vec<u32>> = chunks
+        .par_iter()
+        .map(|chunk| {
+            chunk
+                .names
+                .iter()
+                .zip(&chunk.shard_of)
+                .map(|(name, &shard)| {
+                    let first = shards[shard as usize][name.as_str()];
+                    order.binary_search(&first).unwrap() as u32
+                })
+                .collect()
+        })
+        .collect();
+
+    let buckets: Vec<ShardedEdges<W>> = chunks
+        .par_iter()
+        .zip(&mappings)
+        .map(|(chunk, mapping)| {
+            let mut buckets = vec![Vec::new(); shard_count];
+            for (&(a, b), &accumulator) in &chunk.edges {
+                let (a, b) = (mapping[a as usize], mapping[b as usize]);
+                // Both directions of an undirected edge merge into one.
+                let (a, b) = if Ty::is_directed() {
+                    (a, b)
+                } else {
+                    (a.min(b), a.max(b))
+                };
+                buckets[edge_shard(a, b, shard_count)].push((a, b, accumulator));
+            }
+            buckets
+        })
+        .collect();
+
+    // Buckets are visited in chunk order, so accumulators merge in input order.
+    let shards: Vec<Vec<(u32, u32, Accumulator<W>)>> = (0..shard_count)
+        .into_par_iter()
+        .map(|shard| {
+            let mut merged: HashMap<(u32, u32), Accumulator<W>> = HashMap::new();
+            for chunk in &buckets {
+                for (a, b, accumulator) in &chunk[shard] {
+                    accumulate(&mut merged, (*a, *b), accumulator, options)?;
+                }
+            }
+            Ok(merged
+                .into_iter()
+                .map(|((a, b), accumulator)| (a, b, accumulator))
+                .collect())
+        })
+        .collect::<std::result::Result<_, RecordError>>()?;
+    let mut edges: Vec<(u32, u32, Accumulator<W>)> = shards.into_iter().flatten().collect();
+    edges.par_sort_unstable_by_key(|&(a, b, _)| (a, b));
+
+    let names: Vec<String> = order
+        .par_iter()
+        .map(|&(c, local)| chunks[c as usize].names[local as usize].clone())
+        .collect();
+
+    Ok(Block {
+        names,
+        edges,
+        bad_rows,
+    })
+}
+
+// Node and edge lookup for merging into a graph that is not empty
+struct GraphIndex {
+    nodes: HashMap<String, NodeIndex>,
+    edges: HashMap<(NodeIndex, NodeIndex), EdgeIndex>,
+}
+
+impl GraphIndex {
+    fn new<W, Ty: EdgeType>(graph: &Graph<String, W, Ty>) -> Self {
+        GraphIndex {
+            nodes: graph
+                .node_indices()
+                .map(|node| (graph[node].clone(), node))
+                .collect(),
+            edges: graph
+                .edge_indices()
+                .map(|edge| {
+                    let (a, b) = graph.edge_endpoints(edge).unwrap();
+                    (edge_key::<Ty>(a, b), edge)
+                })
+                .collect(),
+        }
+    }
+}
+
+fn edge_key<Ty: EdgeType>(a: NodeIndex, b: NodeIndex) -> (NodeIndex, NodeIndex) {
+    if Ty::is_directed() {
+        (a, b)
+    } else {
+        (a.min(b), a.max(b))
+    }
+}
+
+// Merges parsed blocks into a graph, which may already hold nodes and edges
+// from earlier loads. Edge weights are only finished once the whole load is
+// in, so a pair that repeats across blocks or files is aggregated as if it
+// had been read at once. An edge that was in the graph before the load
+// counts as a single occurrence with `Aggregation::Mean`.
+pub(crate) struct GraphBuilder<'g, W, Ty> {
+    graph: &'g mut Graph<String, W, Ty>,
+    // Built on first use, so loading into an empty graph skips the lookups
+    index: Option<GraphIndex>,
+    // Size of the graph before the load; everything above was added by it
+    node_count: usize,
+    edge_count: usize,
+    // Occurrences of every edge added by the load, by edge index above `edge_count`
+    added: Vec<Accumulator<W>>,
+    // Original weight and occurrences of edges that were already in the graph
+    updated: HashMap<EdgeIndex, (W, Accumulator<W>)>,
+}
+
+impl<'g, W: EdgeWeight, Ty: EdgeType> GraphBuilder<'g, W, Ty> {
+    pub fn new(graph: &'g mut Graph<String, W, Ty>) -> Self {
+        GraphBuilder {
+            node_count: graph.node_count(),
+            edge_count: graph.edge_count(),
+            graph,
+            index: None,
+            added: Vec::new(),
+            updated: HashMap::new(),
+        }
+    }
+
+    fn add_block(&mut self, block: Block<W>, options: &CsvOptions) -> Result<()> {
+        let (aggregation, overflow) = (options.aggregation, options.overflow);
+        let graph = &mut *self.graph;
+
+        if self.index.is_none() && graph.node_count() == 0 {
+            for name in block.names {
+                graph.add_node(name);
+            }
+            for (a, b, occurrences) in block.edges {
+                graph.add_edge(
+                    NodeIndex::new(a as usize),
+                    NodeIndex::new(b as usize),
+                    W::default(),
+                );
+                self.added.push(occurrences);
+            }
+            return Ok(());
+        }
+
+        let index = self.index.get_or_insert_with(|| GraphIndex::new(graph));
+        let nodes: Vec<NodeIndex> = block
+            .names
+            .into_iter()
+            .map(|name| {
+                *index
+                    .nodes
+                    .entry(name)
+                    .or_insert_with_key(|name| graph.add_node(name.clone()))
+            })
+            .collect();
+
+        for (a, b, occurrences) in block.edges {
+            let (a, b) = edge_key::<Ty>(nodes[a as usize], nodes[b as usize]);
+            match index.edges.get(&(a, b)) {
+                Some(&edge) if edge.index() >= self.edge_count => {
+                    self.added[edge.index() - self.edge_count].merge(
+                        &occurrences,
+                        aggregation,
+                        overflow,
+                    )?;
+                }
+                Some(&edge) => {
+                    let weight = graph[edge];
+                    let (_, total) = self
+                        .updated
+                        .entry(edge)
+                        .or_insert_with(|| (weight, Accumulator::existing(weight, aggregation)));
+                    total.merge(&occurrences, aggregation, overflow)?;
+                }
+                None => {
+                    index
+                        .edges
+                        .insert((a, b), graph.add_edge(a, b, W::default()));
+                    self.added.push(occurrences);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // End the load: on success write the aggregated weights, otherwise remove
+    // everything the load added so the graph is left as it was.
+    pub fn finish<T>(self, result: Result<T>, options: &CsvOptions) -> Result<T> {
+        let weights = result.and_then(|value| {
+            let (aggregation, overflow) = (options.aggregation, options.overflow);
+            let added = self
+                .added
+                .iter()
+                .map(|total| total.finish(aggregation, overflow))
+                .collect::<std::result::Result<Vec<W>, _>>()?;
+            let updated = self
+                .updated
+                .iter()
+                .map(|(&edge, (_, total))| Ok((edge, total.finish(aggregation, overflow)?)))
+                .collect::<std::result::Result<Vec<_>, RecordError>>()?;
+            Ok((value, added, updated))
+        });
+
+        let graph = self.graph;
+        match weights {
+            Ok((value, added, updated)) => {
+                for (i, weight) in added.into_iter().enumerate() {
+                    graph[EdgeIndex::new(self.edge_count + i)] = weight;
+                }
+                for (edge, weight) in updated {
+                    graph[edge] = weight;
+                }
+                Ok(value)
+            }
+            Err(error) => {
+                // Removing the last edge or node never moves another one.
+                while graph.edge_count() > self.edge_count {
+                    graph.remove_edge(EdgeIndex::new(graph.edge_count() - 1));
+                }
+                while graph.node_count() > self.node_count {
+                    graph.remove_node(NodeIndex::new(graph.node_count() - 1));
+                }
+                for (edge, (weight, _)) in self.updated {
+                    graph[edge] = weight;
+                }
+                Err(error)
+            }
+        }
+    }
+}
+
+// Add the interactions read from `reader` to the graph. The input is
+// consumed in blocks of about `block_bytes`, each parsed in parallel, so
+// memory use does not grow with the size of the input.
+pub(crate) fn extend_from_reader<R: Read, W: EdgeWeight, Ty: EdgeType>(
+    builder: &mut GraphBuilder<'_, W, Ty>,
+    mut reader: R,
+    options: &CsvOptions,
+    block_bytes: usize,
+) -> Result<Vec<RecordError>> {
+    let mut bad_rows = Vec::new();
+    let mut buffer = Vec::new();
+    let mut schema: Option<Schema<W>> = None;
+    let mut first_line = 1;
+
+    loop {
+        let mut eof = fill(&mut reader, &mut buffer, block_bytes)?;
+        // Never split a line between blocks.
+        let end = loop {
+            if eof {
+                break buffer.len();
+            }
+            if let Some(newline) = buffer.iter().rposition(|&byte| byte == b'\n') {
+                break newline + 1;
+            }
+            let len = buffer.len() + block_bytes;
+            eof = fill(&mut reader, &mut buffer, len)?;
+        };
+        if end == 0 {
+            break;
+        }
+
+        let block = &buffer[..end];
+        let has_headers = options.has_headers && schema.is_none();
+        let schema = match &schema {
+            Some(schema) => schema,
+            None => schema.insert(options.schema(block)?),
+        };
+        let mut parsed = parse_block::<W, Ty>(block, first_line, has_headers, options, schema)?;
+        first_line += block.iter().filter(|&&byte| byte == b'\n').count() as u64;
+        bad_rows.append(&mut parsed.bad_rows);
+        builder.add_block(parsed, options)?;
+
+        buffer.drain(..end);
+        if eof {
+            break;
+        }
+    }
+
+    Ok(bad_rows)
+}
+
+// Add interactions that are already in memory, e.g. a whole file, to the
+// graph as a single block. Merging into a non-empty graph costs a lookup per
+// edge, so this is faster than streaming when the input fits in memory.
+pub(crate) fn extend_from_bytes<W: EdgeWeight, Ty: EdgeType>(
+    builder: &mut GraphBuilder<'_, W, Ty>,
+    bytes: &[u8],
+    options: &CsvOptions,
+) -> Result<Vec<RecordError>> {
+    let schema = options.schema(bytes)?;
+    let mut parsed = parse_block::<W, Ty>(bytes, 1, options.has_headers, options, &schema)?;
+    let bad_rows = std::mem::take(&mut parsed.bad_rows);
+    builder.add_block(parsed, options)?;
+    Ok(bad_rows)
+}
+
+// Add a graph read from another file format to the graph. Weights, repeated
+// pairs, self-loops and bad rows are handled as for CSV input. Undirected
+// edges are added in both directions when the graph is directed.
+pub(crate) fn extend_from_imported<W: EdgeWeight, Ty: EdgeType>(
+    builder: &mut GraphBuilder<'_, W, Ty>,
+    imported: ImportedGraph,
+    options: &CsvOptions,
+) -> Result<Vec<RecordError>> {
+    let default_weight = W::from_f64(options.default_weight).ok_or_else(|| {
+        Error::Schema(format!(
+            "default weight {} is not a valid edge weight",
+            options.default_weight
+        ))
+    })?;
+
+    let mut bad_rows = imported.bad_rows;
+    let mut edges = HashMap::new();
+    for edge in imported.edges {
+        if edge.source == edge.target && options.self_loops == SelfLoopPolicy::Drop {
+            continue;
+        }
+        let weight = match &edge.weight {
+            Some(value) => parse_weight(value, options.weight_format, default_weight),
+            None => Ok(default_weight),
+        };
+        let weight = match weight {
+            Ok(weight) => weight,
+            Err(kind) => {
+                bad_rows.push(RecordError::new(edge.line, kind));
+                continue;
+            }
+        };
+
+        let (a, b) = (edge.source as u32, edge.target as u32);
+        let occurrence = Accumulator::new(weight, edge.line);
+        let mut add = |key: (u32, u32)| {
+            let key = if Ty::is_directed() {
+                key
+            } else {
+                (key.0.min(key.1), key.0.max(key.1))
+            };
+            accumulate(&mut edges, key, &occurrence, options)
+        };
+        add((a, b))?;
+        if !edge.directed && Ty::is_directed() && a != b {
+            add((b, a))?;
+        }
+    }
+
+    bad_rows.sort_by_key(|error| error.line);
+    match options.bad_rows {
+        BadRowPolicy::Fail if !bad_rows.is_empty() => {
+            return Err(Error::Record(bad_rows.swap_remove(0)));
+        }
+        BadRowPolicy::Fail | BadRowPolicy::Skip => bad_rows.clear(),
+        BadRowPolicy::Collect => {}
+    }
+
+    let mut edges: Vec<(u32, u32, Accumulator<W>)> = edges
+        .into_iter()
+        .map(|((a, b), accumulator)| (a, b, accumulator))
+        .collect();
+    edges.sort_unstable_by_key(|&(a, b, _)| (a, b));
+    let block = Block {
+        names: imported.names,
+        edges,
+        bad_rows: Vec::new(),
+    };
+    builder.add_block(block, options)?;
+    Ok(bad_rows)
+}
+
+// Read until `buffer` holds `len` bytes. Returns whether the input ended.
+fn fill<R: Read>(reader: &mut R, buffer: &mut Vec<u8>, len: usize) -> std::io::Result<bool> {
+    while buffer.len() < len {
+        let wanted = (len - buffer.len()) as u64;
+        if reader.by_ref().take(wanted).read_to_end(buffer)? == 0 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// Expand a path whose file name may contain `*` and `?` wildcards into the
+// matching files, sorted by name. Wildcards in directory names are not
+// supported.
+pub(crate) fn expand_glob(pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let path = Path::new(pattern);
+    let file_pattern = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("");
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    if directory.to_string_lossy().contains(['*', '?']) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "wildcards are only supported in the file name",
+        ));
+    }
+    if !file_pattern.contains(['*', '?']) {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        if entry.file_type()?.is_file()
+            && wildcard_match(file_pattern.as_bytes(), name.to_string_lossy().as_bytes())
+        {
+            files.push(directory.join(name));
+        }
+    }
+    if files.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("no files match {}", pattern),
+        ));
+    }
+    files.sort();
+    Ok(files)
+}
+
+// Linear-time matching: on a mismatch, backtrack only to the latest `*` and
+// let it absorb one more byte.
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Position after the latest `*`, and the name position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                star = Some((p + 1, n));
+                p += 1;
+            }
+            Some(&expected) if expected == b'?' || expected == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((after_star, tried)) => {
+                    p = after_star;
+                    n = tried + 1;
+                    star = Some((after_star, tried + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+// Split `bytes` into roughly equal slices that each end on a newline.
+fn split_lines(bytes: &[u8]) -> Vec<&[u8]> {
+    let target = (bytes.len() / (rayon::current_num_threads() * 4)).max(MIN_CHUNK_BYTES);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        let mut end = (start + target).min(bytes.len());
+        end = match bytes[end..].iter().position(|&byte| byte == b'\n') {
+            Some(offset) => end + offset + 1,
+            None => bytes.len(),
+        };
+        chunks.push(&bytes[start..end]);
+        start = end;
+    }
+
+    chunks
+}
+
+fn parse_chunk<W: EdgeWeight, Ty: EdgeType>(
+    data: &[u8],
+    first_line: u64,
+    has_headers: bool,
+    options: &CsvOptions,
+    schema: &Schema<W>,
+    hasher: &RandomState,
+    shard_count: usize,
+) -> Result<Chunk<W>> {
+    let mut reader = options.reader(has_headers).from_reader(data);
+    let mut chunk = Chunk::new(shard_count);
+    let mut local_ids: HashMap<String, u32> = HashMap::new();
+    let mut record = StringRecord::new();
+
+    let mut intern = |chunk: &mut Chunk<W>, name: &str| -> u32 {
+        if let Some(&id) = local_ids.get(name) {
+            return id;
+        }
+        let id = chunk.names.len() as u32;
+        let shard = (hasher.hash_one(name) % shard_count as u64) as u32;
+        chunk.names.push(name.to_string());
+        chunk.shard_of.push(shard);
+        chunk.names_by_shard[shard as usize].push(id);
+        local_ids.insert(name.to_string(), id);
+        id
+    };
+
+    loop {
+        let (line, parsed) = match reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => {
+                let line = first_line + record.position().map_or(1, |p| p.line()) - 1;
+                let parsed = schema
+                    .parse(&record)
+                    .map_err(|kind| RecordError::new(line, kind));
+                (line, parsed)
+            }
+            Err(error) => {
+                let line = first_line + error.position().map_or(1, |p| p.line()) - 1;
+                let message = error.to_string();
+                match error.into_kind() {
+                    csv::ErrorKind::Io(error) => return Err(Error::Io(error)),
+                    _ => {
+                        let kind = RecordErrorKind::Malformed(message);
+                        (line, Err(RecordError::new(line, kind)))
+                    }
+                }
+            }
+        };
+
+        match parsed {
+            Ok((user1, user2, _))
+                if user1 == user2 && options.self_loops == SelfLoopPolicy::Drop => {}
+            Ok((user1, user2, weight)) => {
+                let user1 = intern(&mut chunk, user1);
+                let user2 = intern(&mut chunk, user2);
+                // `A,B` and `B,A` of an undirected edge must share one
+                // accumulator, so that they merge in input order.
+                let key = if Ty::is_directed() {
+                    (user1, user2)
+                } else {
+                    (user1.min(user2), user1.max(user2))
+                };
+                let occurrence = Accumulator::new(weight, line);
+                accumulate(&mut chunk.edges, key, &occurrence, options)?;
+            }
+            Err(error) => match options.bad_rows {
+                BadRowPolicy::Fail => return Err(Error::Record(error)),
+                BadRowPolicy::Skip => {}
+                BadRowPolicy::Collect => chunk.bad_rows.push(error),
+            },
+        }
+    }
+
+    Ok(chunk)
+}
+
+fn accumulate<W: EdgeWeight>(
+    edges: &mut HashMap<(u32, u32), Accumulator<W>>,
+    key: (u32, u32),
+    occurrence: &Accumulator<W>,
+    options: &CsvOptions,
+) -> std::result::Result<(), RecordError> {
+    match edges.get_mut(&key) {
+        Some(total) => total.merge(occurrence, options.aggregation, options.overflow),
+        None => {
+            edges.insert(key, *occurrence);
+            Ok(())
+        }
+    }
+}
+
+fn edge_shard(a: u32, b: u32, shard_count: usize) -> usize {
+    let key = ((a as u64) << 32 | b as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    (key >> 32) as usize % shard_count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::Directed;
+
+    // Small enough that every few rows start a new block
+    const TINY_BLOCK: usize = 16;
+
+    fn load(
+        graph: &mut Graph<String, u32, Directed>,
+        input: &str,
+        options: &CsvOptions,
+        block_bytes: usize,
+    ) -> Result<Vec<RecordError>> {
+        let mut builder = GraphBuilder::new(graph);
+        let result = extend_from_reader(&mut builder, input.as_bytes(), options, block_bytes);
+        builder.finish(result, options)
+    }
+
+    fn weight(graph: &Graph<String, u32, Directed>, source: &str, target: &str) -> u32 {
+        let node = |name: &str| graph.node_indices().find(|&n| graph[n] == name).unwrap();
+        let edge = graph.find_edge(node(source), node(target)).unwrap();
+        graph[edge]
+    }
+
+    fn with_aggregation(aggregation: Aggregation) -> CsvOptions {
+        CsvOptions {
+            aggregation,
+            ..CsvOptions::default()
+        }
+    }
+
+    #[test]
+    fn mean_spans_blocks() {
+        let mut input = String::from("source,target,weight\na,b,1\na,b,1\n");
+        for i in 0..50 {
+            input.push_str(&format!("c{},d,1\n", i));
+        }
+        input.push_str("a,b,10\n");
+
+        let mut graph = Graph::new();
+        load(
+            &mut graph,
+            &input,
+            &with_aggregation(Aggregation::Mean),
+            TINY_BLOCK,
+        )
+        .unwrap();
+        assert_eq!(weight(&graph, "a", "b"), 4);
+    }
+
+    #[test]
+    fn blocks_do_not_change_any_aggregation() {
+        let input = "source,target,weight\na,b,3\nb,c,1\na,b,8\nc,a,2\na,b,2\nb,c,6\na,b,5\n";
+        for aggregation in [
+            Aggregation::Sum,
+            Aggregation::Max,
+            Aggregation::Min,
+            Aggregation::Mean,
+            Aggregation::Count,
+            Aggregation::Last,
+        ] {
+            let options = with_aggregation(aggregation);
+            let mut whole = Graph::new();
+            load(&mut whole, input, &options, BLOCK_BYTES).unwrap();
+            let mut streamed = Graph::new();
+            load(&mut streamed, input, &options, TINY_BLOCK).unwrap();
+
+            for (source, target) in [("a", "b"), ("b", "c"), ("c", "a")] {
+                assert_eq!(
+                    weight(&whole, source, target),
+                    weight(&streamed, source, target),
+                    "{:?} of {},{}",
+                    aggregation,
+                    source,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn existing_edge_counts_once_in_mean() {
+        let options = with_aggregation(Aggregation::Mean);
+        let mut graph = Graph::new();
+        load(
+            &mut graph,
+            "source,target,weight\na,b,2\na,b,4\n",
+            &options,
+            TINY_BLOCK,
+        )
+        .unwrap();
+        assert_eq!(weight(&graph, "a", "b"), 3);
+
+        load(
+            &mut graph,
+            "source,target,weight\na,b,6\nc,d,1\na,b,9\n",
+            &options,
+            TINY_BLOCK,
+        )
+        .unwrap();
+        assert_eq!(weight(&graph, "a", "b"), 6);
+    }
+
+    #[test]
+    fn failed_load_leaves_graph_unchanged() {
+        let options = CsvOptions::default();
+        let mut graph = Graph::new();
+        load(
+            &mut graph,
+            "source,target,weight\na,b,2\nb,c,1\n",
+            &options,
+            BLOCK_BYTES,
+        )
+        .unwrap();
+
+        let input = "source,target,weight\na,b,5\nc,d,1\nd,e,1\ne,f,1\nf,x,oops\n";
+        let error = load(&mut graph, input, &options, TINY_BLOCK).unwrap_err();
+        assert!(matches!(error, Error::Record(RecordError { line: 6, .. })));
+
+        let names: Vec<&str> = graph.node_weights().map(String::as_str).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(weight(&graph, "a", "b"), 2);
+        assert_eq!(weight(&graph, "b", "c"), 1);
+    }
+
+    #[test]
+    fn overflow_in_a_later_block_fails_the_load() {
+        let options = CsvOptions {
+            overflow: OverflowPolicy::Error,
+            ..CsvOptions::default()
+        };
+        let input = format!(
+            "source,target,weight\na,b,{}\nc,d,1\nd,e,1\na,b,1\n",
+            u32::MAX
+        );
+        let mut graph = Graph::new();
+        let error = load(&mut graph, &input, &options, TINY_BLOCK).unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Record(RecordError {
+                line: 5,
+                kind: RecordErrorKind::WeightOverflow,
+                ..
+            })
+        ));
+        assert_eq!(graph.node_count(), 0);
+    }
+
+    #[test]
+    fn wildcards() {
+        for (pattern, name) in [
+            ("*", ""),
+            ("*", "day-1.csv"),
+            ("day-?.csv", "day-1.csv"),
+            ("day-*.csv", "day-.csv"),
+            ("*.csv", "a.b.csv"),
+            ("*a*b", "xaxxab"),
+            ("a**b", "ab"),
+            ("day-1.csv", "day-1.csv"),
+        ] {
+            assert!(
+                wildcard_match(pattern.as_bytes(), name.as_bytes()),
+                "{} {}",
+                pattern,
+                name
+            );
+        }
+        for (pattern, name) in [
+            ("?", ""),
+            ("day-?.csv", "day-10.csv"),
+            ("*.csv", "day.csv.gz"),
+            ("*a*b", "xaxxabc"),
+            ("day-1.csv", "day-2.csv"),
+        ] {
+            assert!(
+                !wildcard_match(pattern.as_bytes(), name.as_bytes()),
+                "{} {}",
+                pattern,
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn wildcards_do_not_backtrack_exponentially() {
+        let pattern = "*a".repeat(30) + "b";
+        let name = "a".repeat(200);
+        assert!(!wildcard_match(pattern.as_bytes(), name.as_bytes()));
+    }
+}
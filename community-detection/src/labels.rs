@@ -0,0 +1,73 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+// Relabel communities so that ids only depend on the partition itself: the
+// largest community gets id 0, and communities of equal size are ordered by
+// their smallest member name.
+pub fn canonical_labels(labels: &HashMap<String, usize>) -> HashMap<String, usize> {
+    let mut communities: HashMap<usize, (usize, &String)> = HashMap::new();
+    for (user, &id) in labels {
+        let (size, smallest) = communities.entry(id).or_insert((0, user));
+        *size += 1;
+        *smallest = (*smallest).min(user);
+    }
+
+    let mut order: Vec<(usize, (usize, &String))> = communities.into_iter().collect();
+    order.sort_unstable_by_key(|&(_, (size, smallest))| (Reverse(size), smallest));
+    let new_ids: HashMap<usize, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(new_id, &(id, _))| (id, new_id))
+        .collect();
+
+    labels
+        .iter()
+        .map(|(user, id)| (user.clone(), new_ids[id]))
+        .collect()
+}
+
+// Give every community in `labels` the id of the community in `previous` it
+// shares the most users with, so ids stay stable between runs. Pairs are
+// matched greedily from the largest overlap down, and every previous id is
+// used at most once. Communities without a match get new ids above the
+// largest previous id, in canonical order.
+pub fn match_labels(
+    labels: &HashMap<String, usize>,
+    previous: &HashMap<String, usize>,
+) -> HashMap<String, usize> {
+    let labels = canonical_labels(labels);
+
+    let mut overlaps: HashMap<(usize, usize), usize> = HashMap::new();
+    for (user, &id) in &labels {
+        if let Some(&previous_id) = previous.get(user) {
+            *overlaps.entry((id, previous_id)).or_default() += 1;
+        }
+    }
+    let mut overlaps: Vec<((usize, usize), usize)> = overlaps.into_iter().collect();
+    overlaps.sort_unstable_by_key(|&(pair, overlap)| (Reverse(overlap), pair));
+
+    let community_count = labels.values().max().map_or(0, |&id| id + 1);
+    let mut new_ids: Vec<Option<usize>> = vec![None; community_count];
+    let mut taken = HashSet::new();
+    for ((id, previous_id), _) in overlaps {
+        if new_ids[id].is_none() && taken.insert(previous_id) {
+            new_ids[id] = Some(previous_id);
+        }
+    }
+
+    let mut next_id = previous.values().max().map_or(0, |&id| id + 1);
+    let new_ids: Vec<usize> = new_ids
+        .into_iter()
+        .map(|new_id| {
+            new_id.unwrap_or_else(|| {
+                next_id += 1;
+                next_id - 1
+            })
+        })
+        .collect();
+
+    labels
+        .into_iter()
+        .map(|(user, id)| (user, new_ids[id]))
+        .collect()
+}
@@ -1,133 +1,546 @@
-use csv::Reader;
-use petgraph::algo::tarjan_scc;
-use petgraph::dot::{Dot, Config};
-use petgraph::Graph;
+use compression::Decompressed;
+use export::{Table, Values};
+use ingest::GraphBuilder;
+use petgraph::dot::{Config, Dot};
+use petgraph::{Directed, EdgeType, Graph, Undirected};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::{thread_rng, Rng};
+use rand::{Rng, SeedableRng, thread_rng};
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufWriter, Read, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
 use std::process::Command;
 use std::sync::{Arc, Mutex};
 
+pub mod aggregation;
+pub mod algorithms;
+pub mod comparison;
+mod compression;
+pub mod error;
+pub mod export;
+pub mod formats;
+pub mod generators;
+mod ingest;
+pub mod labels;
+pub mod metrics;
+pub mod quotient;
+pub mod weight;
+
+pub use aggregation::{Aggregation, OverflowPolicy};
+pub use algorithms::{
+    CommunityAlgorithm, LabelPropagation, Leiden, Louvain, Partition, StronglyConnected,
+};
+pub use comparison::{
+    PartitionComparison, adjusted_rand_index, compare_partitions, load_partition_csv,
+    normalized_mutual_information, variation_of_information,
+};
+pub use compression::Compression;
+pub use error::{Error, RecordError, RecordErrorKind};
+pub use export::ExportFormat;
+pub use formats::GraphFormat;
+pub use generators::{
+    LfrConfig, PlantedPartitionConfig, WeightDistribution, generate_lfr_csv,
+    generate_planted_partition_csv,
+};
+pub use ingest::{BadRowPolicy, Column, CsvOptions, SelfLoopPolicy, WeightFormat};
+pub use labels::{canonical_labels, match_labels};
+pub use metrics::{CommunityQuality, PartitionQuality, partition_quality};
+pub use quotient::{CommunityNode, quotient_graph};
+pub use weight::EdgeWeight;
+
 pub struct UsernameGenerator {
-    prefixes: Vec<&'static str>,
-    suffixes: Vec<&'static str>,
+    prefixes: Vec<String>,
+    suffixes: Vec<String>,
+    numbers: RangeInclusive<u32>,
     used_names: Arc<Mutex<HashSet<String>>>,
 }
 
+// Returned when a generator cannot produce the requested number of unique names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NamespaceExhausted {
+    pub requested: usize,
+    pub available: usize,
+}
+
+impl fmt::Display for NamespaceExhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "requested {} unique usernames but only {} are left in the namespace",
+            self.requested, self.available
+        )
+    }
+}
+
+impl std::error::Error for NamespaceExhausted {}
+
+impl From<NamespaceExhausted> for std::io::Error {
+    fn from(error: NamespaceExhausted) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+    }
+}
+
 impl UsernameGenerator {
     pub fn new() -> Self {
-        UsernameGenerator {
-            prefixes: vec![
-                "dark", "shadow", "light", "blue", "red", "green", "gold", "silver",
-                "phantom", "ninja", "stealth", "epic", "legend", "super", "mega",
+        Self::with_vocabulary(
+            [
+                "dark", "shadow", "light", "blue", "red", "green", "gold", "silver", "phantom",
+                "ninja", "stealth", "epic", "legend", "super", "mega",
             ],
-            suffixes: vec![
-                "warrior", "hunter", "mage", "slayer", "knight", "rogue", "wizard",
-                "assassin", "lord", "king", "queen", "master", "pro", "noob", "gamer",
+            [
+                "warrior", "hunter", "mage", "slayer", "knight", "rogue", "wizard", "assassin",
+                "lord", "king", "queen", "master", "pro", "noob", "gamer",
             ],
+            1..=998,
+        )
+    }
+
+    // Names are built as `{prefix}{suffix}{number}`
+    pub fn with_vocabulary<S: Into<String>>(
+        prefixes: impl IntoIterator<Item = S>,
+        suffixes: impl IntoIterator<Item = S>,
+        numbers: RangeInclusive<u32>,
+    ) -> Self {
+        UsernameGenerator {
+            prefixes: prefixes.into_iter().map(Into::into).collect(),
+            suffixes: suffixes.into_iter().map(Into::into).collect(),
+            numbers,
             used_names: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
-    // Parallel generation of unique usernames
-    pub fn generate_unique_batch(&self, count: usize) -> Vec<String> {
-        (0..count)
-            .into_par_iter()
-            .map_init(
-                || thread_rng(),
-                |rng, _| {
-                    let prefix = self.prefixes.choose(rng).unwrap();
-                    let suffix = self.suffixes.choose(rng).unwrap();
-                    let num = rng.gen_range(1..999);
-                    format!("{}{}{}", prefix, suffix, num)
-                },
-            )
-            .filter(|name| {
-                let mut used = self.used_names.lock().unwrap();
-                used.insert(name.clone())
-            })
-            .collect()
+    // Number of distinct names the vocabulary can produce
+    pub fn capacity(&self) -> usize {
+        let numbers = if self.numbers.is_empty() {
+            0
+        } else {
+            (*self.numbers.end() - *self.numbers.start()) as usize + 1
+        };
+        self.prefixes
+            .len()
+            .saturating_mul(self.suffixes.len())
+            .saturating_mul(numbers)
+    }
+
+    // Parallel generation of exactly `count` unique usernames
+    pub fn generate_unique_batch(&self, count: usize) -> Result<Vec<String>, NamespaceExhausted> {
+        self.generate_unique_batch_seeded(count, thread_rng().r#gen())
+    }
+
+    // Same as `generate_unique_batch`, but reproducible: the result depends
+    // on `seed` and on the names this generator has already handed out, not
+    // on the number of rayon threads. A fresh generator always gives the same
+    // names for the same seed.
+    pub fn generate_unique_batch_seeded(
+        &self,
+        count: usize,
+        seed: u64,
+    ) -> Result<Vec<String>, NamespaceExhausted> {
+        let capacity = self.capacity();
+        let mut used = self.used_names.lock().unwrap();
+        let available = capacity.saturating_sub(used.len());
+        if count > available {
+            return Err(NamespaceExhausted {
+                requested: count,
+                available,
+            });
+        }
+
+        // Rejection sampling slows down as the namespace fills up, so switch
+        // to drawing from the list of remaining names.
+        if count.saturating_mul(2) > available {
+            return self.sample_remaining(Vec::new(), count, seed, &mut used);
+        }
+
+        let mut names = Vec::with_capacity(count);
+        let mut next_chunk = 0;
+        while names.len() < count {
+            let missing = count - names.len();
+            let chunks = missing.div_ceil(CHUNK_SIZE);
+            let candidates: Vec<String> = (next_chunk..next_chunk + chunks)
+                .into_par_iter()
+                .flat_map_iter(|chunk| {
+                    let mut rng = chunk_rng(seed, NAME_STREAM, chunk);
+                    (0..CHUNK_SIZE).map(move |_| self.name_at(rng.gen_range(0..capacity)))
+                })
+                .collect();
+            next_chunk += chunks;
+
+            let before = names.len();
+            names.extend(
+                candidates
+                    .into_iter()
+                    .filter(|name| used.insert(name.clone()))
+                    .take(missing),
+            );
+            // Different vocabulary entries can spell the same name, so the
+            // real namespace may be smaller than `capacity`.
+            if names.len() == before {
+                return self.sample_remaining(names, count, seed, &mut used);
+            }
+        }
+
+        Ok(names)
+    }
+
+    // Fill `names` up to `count` from the names that are still unused
+    fn sample_remaining(
+        &self,
+        mut names: Vec<String>,
+        count: usize,
+        seed: u64,
+        used: &mut HashSet<String>,
+    ) -> Result<Vec<String>, NamespaceExhausted> {
+        let mut remaining: Vec<String> = (0..self.capacity())
+            .map(|index| self.name_at(index))
+            .filter(|name| !used.contains(name))
+            .collect();
+        remaining.sort_unstable();
+        remaining.dedup();
+
+        let missing = count - names.len();
+        if remaining.len() < missing {
+            for name in &names {
+                used.remove(name);
+            }
+            return Err(NamespaceExhausted {
+                requested: count,
+                available: names.len() + remaining.len(),
+            });
+        }
+
+        let mut rng = chunk_rng(seed, NAME_STREAM, usize::MAX);
+        remaining.shuffle(&mut rng);
+        remaining.truncate(missing);
+        used.extend(remaining.iter().cloned());
+        names.extend(remaining);
+        Ok(names)
+    }
+
+    fn name_at(&self, index: usize) -> String {
+        let prefix = &self.prefixes[index % self.prefixes.len()];
+        let index = index / self.prefixes.len();
+        let suffix = &self.suffixes[index % self.suffixes.len()];
+        let num = *self.numbers.start() as usize + index / self.suffixes.len();
+        format!("{}{}{}", prefix, suffix, num)
     }
 }
 
+impl Default for UsernameGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Random work is split into fixed-size chunks, each with its own generator
+// derived from the seed, so results do not depend on how rayon schedules them.
+const CHUNK_SIZE: usize = 1024;
+// Chunks written to the output file per parallel batch
+const CHUNKS_PER_BATCH: usize = 64;
+
+pub(crate) const NAME_STREAM: u64 = 0;
+pub(crate) const INTERACTION_STREAM: u64 = 1;
+
+pub(crate) fn chunk_rng(seed: u64, stream: u64, chunk: usize) -> StdRng {
+    let mut bytes = [0u8; 32];
+    bytes[..8].copy_from_slice(&seed.to_le_bytes());
+    bytes[8..16].copy_from_slice(&stream.to_le_bytes());
+    bytes[16..24].copy_from_slice(&(chunk as u64).to_le_bytes());
+    StdRng::from_seed(bytes)
+}
+
+// Header row written by the generators, and expected by `CsvOptions::default()`
+pub const INTERACTION_HEADER: &str = "source,target,weight";
+
+// Write `num_interactions` random `source,target,weight` rows below an
+// `INTERACTION_HEADER` row
 pub fn generate_interaction_csv(
     num_users: usize,
     num_interactions: usize,
     filename: &str,
+) -> std::io::Result<()> {
+    generate_interaction_csv_seeded(num_users, num_interactions, filename, thread_rng().r#gen())
+}
+
+// Byte-identical output for a given seed, regardless of thread count
+pub fn generate_interaction_csv_seeded(
+    num_users: usize,
+    num_interactions: usize,
+    filename: &str,
+    seed: u64,
 ) -> std::io::Result<()> {
     let generator = UsernameGenerator::new();
-    let users = Arc::new(generator.generate_unique_batch(num_users));
+    let users = generator.generate_unique_batch_seeded(num_users, seed)?;
 
     let file = File::create(filename)?;
-    let writer = Arc::new(Mutex::new(BufWriter::new(file)));
+    let mut writer = BufWriter::new(file);
+    writeln!(writer, "{}", INTERACTION_HEADER)?;
 
-    (0..num_interactions).into_par_iter().for_each(|_| {
-        let mut rng = thread_rng();
-        let user1 = users.choose(&mut rng).unwrap();
-        let user2 = users.choose(&mut rng).unwrap();
-        let weight = rng.gen_range(1..=20);
+    let chunks: Vec<usize> = (0..num_interactions.div_ceil(CHUNK_SIZE)).collect();
+    for batch in chunks.chunks(CHUNKS_PER_BATCH) {
+        let rows: Vec<String> = batch
+            .par_iter()
+            .map(|&chunk| {
+                let mut rng = chunk_rng(seed, INTERACTION_STREAM, chunk);
+                let len = CHUNK_SIZE.min(num_interactions - chunk * CHUNK_SIZE);
+                let mut rows = String::new();
+                for _ in 0..len {
+                    let user1 = users.choose(&mut rng).unwrap();
+                    let user2 = users.choose(&mut rng).unwrap();
+                    let weight = rng.gen_range(1..=20);
+                    rows.push_str(&format!("{},{},{}\n", user1, user2, weight));
+                }
+                rows
+            })
+            .collect();
 
-        let mut writer = writer.lock().unwrap();
-        writeln!(writer, "{},{},{}", user1, user2, weight).unwrap();
-    });
+        for chunk in rows {
+            writer.write_all(chunk.as_bytes())?;
+        }
+    }
 
-    Ok(())
+    writer.flush()
 }
 
-pub struct CommunityDetector {
-    pub graph: Graph<String, u32>,
+// `Ty` selects directed or undirected interactions and `W` the edge weight
+// type, e.g. `CommunityDetector<Directed, f64>` for similarity scores.
+pub struct CommunityDetector<Ty = Directed, W = u32> {
+    pub graph: Graph<String, W, Ty>,
     pub labels: HashMap<String, usize>,
 }
 
+// Detector for symmetric interactions: `A,B` and `B,A` load as one edge
+// carrying their combined weight.
+pub type UndirectedCommunityDetector = CommunityDetector<Undirected>;
+
 impl CommunityDetector {
-    pub fn from_csv(filename: &str) -> Result<Self, csv::Error> {
+    pub fn from_csv(filename: &str) -> error::Result<Self> {
         let graph = Self::build_graph_from_csv_parallel(filename)?;
         let labels = HashMap::new();
         Ok(CommunityDetector { graph, labels })
     }
 
-    // Parallel CSV parsing and graph construction
-    pub fn build_graph_from_csv_parallel(filename: &str) -> Result<Graph<String, u32>, csv::Error> {
-        let graph = Arc::new(Mutex::new(Graph::new()));
-        let node_indices = Arc::new(Mutex::new(HashMap::new()));
-
-        Reader::from_path(filename)?
-            .into_records()
-            .par_bridge() // Parallel bridge for rayon
-            .for_each(|record| {
-                let record = record.unwrap();
-                let user1 = record[0].to_string();
-                let user2 = record[1].to_string();
-                let weight: u32 = record[2].parse().unwrap_or(1);
-
-                let mut graph = graph.lock().unwrap();
-                let mut node_indices = node_indices.lock().unwrap();
-
-                let node1 = *node_indices
-                    .entry(user1.clone())
-                    .or_insert_with(|| graph.add_node(user1));
-                let node2 = *node_indices
-                    .entry(user2.clone())
-                    .or_insert_with(|| graph.add_node(user2));
-
-                if let Some(edge) = graph.find_edge(node1, node2) {
-                    graph[edge] += weight;
-                } else {
-                    graph.add_edge(node1, node2, weight);
-                }
-            });
+    // Like `from_csv`, but with a configurable layout and bad-row policy. Rows
+    // that were not loaded are returned when the policy is `Collect`.
+    pub fn from_csv_with_options(
+        filename: &str,
+        options: &CsvOptions,
+    ) -> error::Result<(Self, Vec<RecordError>)> {
+        Self::load_csv(filename, options)
+    }
+
+    // Parallel CSV parsing and graph construction, see `ingest` for details
+    pub fn build_graph_from_csv_parallel(filename: &str) -> error::Result<Graph<String, u32>> {
+        let (detector, _) = Self::load_csv(filename, &CsvOptions::default())?;
+        Ok(detector.graph)
+    }
+
+    pub fn render_and_open_graph(dot_file: &str, output_image: &str) -> std::io::Result<()> {
+        Command::new("dot")
+            .args(["-Tpng", dot_file, "-o", output_image])
+            .status()?;
+
+        let opener = if cfg!(target_os = "windows") {
+            "start"
+        } else if cfg!(target_os = "macos") {
+            "open"
+        } else {
+            "xdg-open"
+        };
+
+        Command::new(opener).arg(output_image).status()?;
+        Ok(())
+    }
+}
+
+impl<Ty: EdgeType, W> Default for CommunityDetector<Ty, W> {
+    fn default() -> Self {
+        CommunityDetector {
+            graph: Graph::default(),
+            labels: HashMap::new(),
+        }
+    }
+}
+
+impl CommunityDetector<Undirected> {
+    pub fn from_csv_undirected(filename: &str) -> error::Result<Self> {
+        let (detector, _) = Self::load_csv(filename, &CsvOptions::default())?;
+        Ok(detector)
+    }
+
+    pub fn from_csv_undirected_with_options(
+        filename: &str,
+        options: &CsvOptions,
+    ) -> error::Result<(Self, Vec<RecordError>)> {
+        Self::load_csv(filename, options)
+    }
+}
+
+impl<Ty: EdgeType + Sync, W: EdgeWeight> CommunityDetector<Ty, W> {
+    // Load a CSV into any edge and weight type, e.g.
+    // `CommunityDetector::<Undirected, f64>::load_csv(filename, &options)`
+    pub fn load_csv(
+        filename: &str,
+        options: &CsvOptions,
+    ) -> error::Result<(Self, Vec<RecordError>)> {
+        let mut detector = Self::default();
+        let bad_rows = detector.extend_from_csv(filename, options)?;
+        Ok((detector, bad_rows))
+    }
+
+    // Load a graph in any supported format, e.g.
+    // `CommunityDetector::<Undirected>::load_file("karate.gml", GraphFormat::Gml, &options)`
+    pub fn load_file(
+        filename: &str,
+        format: GraphFormat,
+        options: &CsvOptions,
+    ) -> error::Result<(Self, Vec<RecordError>)> {
+        let mut detector = Self::default();
+        let bad_rows = detector.extend_from_file(filename, format, options)?;
+        Ok((detector, bad_rows))
+    }
+
+    // Add the interactions read from any source, e.g. `std::io::stdin()`, to
+    // the graph. Users already in the graph are reused and repeated pairs are
+    // aggregated with the existing edges as set by `options.aggregation`. The
+    // input is processed in blocks, so it does not have to fit in memory.
+    // Labels from an earlier detection are left as they are until detection
+    // runs again. If loading fails, the graph is left as it was.
+    pub fn extend_from_reader<R: Read>(
+        &mut self,
+        reader: R,
+        options: &CsvOptions,
+    ) -> error::Result<Vec<RecordError>> {
+        let mut builder = GraphBuilder::new(&mut self.graph);
+        let result = ingest::extend_from_reader(&mut builder, reader, options, ingest::BLOCK_BYTES);
+        builder.finish(result, options)
+    }
+
+    pub fn extend_from_csv<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+        options: &CsvOptions,
+    ) -> error::Result<Vec<RecordError>> {
+        self.extend_from_files([filename], options)
+    }
+
+    // Load several CSV files into one graph, in the given order. Every file
+    // starts with its own header row when `options.has_headers` is set, and
+    // bad rows carry the file they came from. Repeated pairs are aggregated
+    // across files. If any file fails to load, none of them is added.
+    pub fn extend_from_files<P: AsRef<Path>>(
+        &mut self,
+        filenames: impl IntoIterator<Item = P>,
+        options: &CsvOptions,
+    ) -> error::Result<Vec<RecordError>> {
+        let files = filenames
+            .into_iter()
+            .map(|filename| (filename, GraphFormat::Csv));
+        let mut builder = GraphBuilder::new(&mut self.graph);
+        let result = read_files(&mut builder, files, options);
+        builder.finish(result, options)
+    }
+
+    // Add a graph file in any supported format, see `GraphFormat::from_path`
+    // to pick it by extension. Plain files are read into memory whole; gzip
+    // and zstd files (by extension or magic bytes) are decompressed while
+    // they are read, and CSV is streamed from the decoder. For formats
+    // other than CSV only the weight, aggregation, self-loop and bad-row
+    // settings of `options` apply. If loading fails, the graph is left as it
+    // was.
+    pub fn extend_from_file<P: AsRef<Path>>(
+        &mut self,
+        filename: P,
+        format: GraphFormat,
+        options: &CsvOptions,
+    ) -> error::Result<Vec<RecordError>> {
+        let mut builder = GraphBuilder::new(&mut self.graph);
+        let result = read_files(&mut builder, [(filename, format)], options);
+        builder.finish(result, options)
+    }
+
+    // Load every file matching `pattern`, e.g. `logs/interactions-*.csv`, in
+    // name order. Wildcards (`*` and `?`) may only appear in the file name.
+    // A pattern that matches no file is an error.
+    pub fn extend_from_glob(
+        &mut self,
+        pattern: &str,
+        options: &CsvOptions,
+    ) -> error::Result<Vec<RecordError>> {
+        let filenames = ingest::expand_glob(pattern)?;
+        self.extend_from_files(filenames, options)
+    }
+
+    // Add users that are not in the graph yet, e.g. users without any
+    // interactions. They end up as singleton communities. Returns how many
+    // nodes were added.
+    pub fn add_nodes<S: Into<String>>(&mut self, users: impl IntoIterator<Item = S>) -> usize {
+        let mut known: HashSet<String> = self.graph.node_weights().cloned().collect();
+        let before = self.graph.node_count();
+        for user in users {
+            let user = user.into();
+            if known.insert(user.clone()) {
+                self.graph.add_node(user);
+            }
+        }
+        self.graph.node_count() - before
+    }
 
-        Ok(Arc::try_unwrap(graph).unwrap().into_inner().unwrap())
+    // Add the users listed in the first column of a CSV with a header row,
+    // such as a `user,community` ground truth file
+    pub fn add_nodes_from_csv(&mut self, filename: &str) -> error::Result<usize> {
+        let mut users = Vec::new();
+        for record in csv::Reader::from_path(filename)?.into_records() {
+            let record = record?;
+            match record.get(0).map(str::trim) {
+                Some(user) if !user.is_empty() => users.push(user.to_string()),
+                _ => {
+                    let line = record.position().map_or(0, |position| position.line());
+                    return Err(Error::Record(RecordError::new(
+                        line,
+                        RecordErrorKind::MissingColumn(0),
+                    )));
+                }
+            }
+        }
+        Ok(self.add_nodes(users))
     }
 
     pub fn detect_communities(&mut self) {
-        let scc = tarjan_scc(&self.graph);
-        
+        self.detect_communities_with(&StronglyConnected);
+    }
+
+    // Run any community detection algorithm and store its result in `labels`
+    pub fn detect_communities_with<A: CommunityAlgorithm<Ty, W> + ?Sized>(
+        &mut self,
+        algorithm: &A,
+    ) {
+        let partition = algorithm.detect(&self.graph);
+        self.assign_labels(partition);
+    }
+
+    // Modularity-based communities using the edge weights of `graph`
+    pub fn detect_communities_louvain(&mut self) {
+        self.detect_communities_with(&Louvain::default());
+    }
+
+    // Like Louvain, but every community is guaranteed to be connected
+    pub fn detect_communities_leiden(&mut self) {
+        self.detect_communities_with(&Leiden::default());
+    }
+
+    // Near-linear weighted label propagation, reproducible for a given seed
+    pub fn detect_communities_label_propagation(&mut self, seed: u64, max_iterations: usize) {
+        self.detect_communities_with(&LabelPropagation::new(seed, max_iterations));
+    }
+
+    fn assign_labels(&mut self, partition: Partition) {
         // Parallel community labeling
-        self.labels = scc
+        let labels = partition
             .into_par_iter()
             .enumerate()
             .flat_map(|(community_id, nodes)| {
@@ -137,6 +550,20 @@ impl CommunityDetector {
                     .collect::<Vec<_>>()
             })
             .collect();
+        // Algorithms number communities in whatever order they find them.
+        self.labels = canonical_labels(&labels);
+    }
+
+    // Renumber `labels` canonically, see `canonical_labels`. Detection does
+    // this already; use it after setting `labels` by hand.
+    pub fn canonicalize_labels(&mut self) {
+        self.labels = canonical_labels(&self.labels);
+    }
+
+    // Renumber `labels` to match a previous run's ids by maximum overlap,
+    // e.g. with `previous` from `load_partition_csv`. See `match_labels`.
+    pub fn match_labels_to(&mut self, previous: &HashMap<String, usize>) {
+        self.labels = match_labels(&self.labels, previous);
     }
 
     pub fn get_communities(&self) -> HashMap<usize, Vec<String>> {
@@ -150,24 +577,85 @@ impl CommunityDetector {
         communities
     }
 
-    pub fn save_graph_to_dot(
+    // Modularity, coverage, performance and per-community conductance of `labels`
+    pub fn quality(&self) -> PartitionQuality {
+        partition_quality(&self.graph, &self.labels)
+    }
+
+    // NMI, ARI and variation of information of `labels` against a reference partition
+    pub fn compare_with(&self, reference: &HashMap<String, usize>) -> PartitionComparison {
+        compare_partitions(&self.labels, reference)
+    }
+
+    // Write a `user,community_id` row for every labelled user, ordered by
+    // community and then by name
+    pub fn export_assignments(&self, filename: &str, format: ExportFormat) -> std::io::Result<()> {
+        let mut assignments: Vec<(usize, &String)> =
+            self.labels.iter().map(|(user, &id)| (id, user)).collect();
+        assignments.sort_unstable();
+        let table = Table {
+            columns: vec![
+                (
+                    "user",
+                    Values::Text(assignments.iter().map(|&(_, user)| user.clone()).collect()),
+                ),
+                (
+                    "community_id",
+                    Values::Integer(assignments.iter().map(|&(id, _)| id as i64).collect()),
+                ),
+            ],
+        };
+        table.write(filename, format)
+    }
+
+    // Write one row per detected community with the statistics of `quality`
+    pub fn export_community_summaries(
         &self,
         filename: &str,
+        format: ExportFormat,
     ) -> std::io::Result<()> {
+        let labelled: HashSet<usize> = self.labels.values().copied().collect();
+        let communities: Vec<CommunityQuality> = self
+            .quality()
+            .communities
+            .into_iter()
+            .filter(|community| labelled.contains(&community.community_id))
+            .collect();
+        let integers = |field: fn(&CommunityQuality) -> usize| {
+            Values::Integer(communities.iter().map(|c| field(c) as i64).collect())
+        };
+        let floats = |field: fn(&CommunityQuality) -> f64| {
+            Values::Float(communities.iter().map(field).collect())
+        };
+        let table = Table {
+            columns: vec![
+                ("community_id", integers(|c| c.community_id)),
+                ("size", integers(|c| c.size)),
+                ("internal_weight", floats(|c| c.internal_weight)),
+                ("boundary_weight", floats(|c| c.boundary_weight)),
+                ("volume", floats(|c| c.volume)),
+                ("conductance", floats(|c| c.conductance)),
+            ],
+        };
+        table.write(filename, format)
+    }
+
+    pub fn save_graph_to_dot(&self, filename: &str) -> std::io::Result<()> {
         let node_to_community: HashMap<_, _> = self
             .labels
             .par_iter()
             .map(|(user, comm_id)| (user.clone(), *comm_id))
             .collect();
 
-        let binding = |_, (_, username)| {
-                let comm_id = node_to_community.get(username).unwrap();
-                let hue = (comm_id * 60) % 360;
-                format!(
-                    "label=\"{}\", style=filled, fillcolor=\"{:.1} 0.5 0.7\"",
-                    username, hue as f32
-                )
-            };
+        // Users without a community, e.g. added after detection, are drawn unfilled
+        let binding = |_, (_, username)| match node_to_community.get(username) {
+            Some(comm_id) => format!(
+                "label=\"{}\", style=filled, fillcolor=\"{}\"",
+                username,
+                community_color(*comm_id)
+            ),
+            None => format!("label=\"{}\", style=dashed", username),
+        };
         let dot = Dot::with_attr_getters(
             &self.graph,
             &[Config::EdgeNoLabel],
@@ -178,21 +666,103 @@ impl CommunityDetector {
         std::fs::write(filename, format!("{:?}", dot))
     }
 
-    pub fn render_and_open_graph(dot_file: &str, output_image: &str) -> std::io::Result<()> {
-        Command::new("dot")
-            .args(&["-Tpng", dot_file, "-o", output_image])
-            .status()?;
+    // Community-level view of the graph, see `quotient_graph`
+    pub fn quotient_graph(&self) -> Graph<CommunityNode, W, Ty> {
+        quotient_graph(&self.graph, &self.labels)
+    }
 
-        let opener = if cfg!(target_os = "windows") {
-            "start"
-        } else if cfg!(target_os = "macos") {
-            "open"
-        } else {
-            "xdg-open"
+    // Like `save_graph_to_dot`, for `quotient_graph`. Communities keep the
+    // colors they have in the full graph.
+    pub fn save_quotient_graph_to_dot(&self, filename: &str) -> std::io::Result<()> {
+        let quotient = self.quotient_graph();
+        let dot = Dot::with_attr_getters(
+            &quotient,
+            &[Config::NodeNoLabel, Config::EdgeNoLabel],
+            &|_, edge| format!("label=\"{}\"", edge.weight()),
+            &|_, (_, community)| {
+                format!(
+                    "label=\"{}\", style=filled, fillcolor=\"{}\"",
+                    community,
+                    community_color(community.community_id)
+                )
+            },
+        );
+
+        std::fs::write(filename, format!("{:?}", dot))
+    }
+
+    // GraphML with `community`, `degree` and edge `weight` attributes, for
+    // graphs too large for DOT; opens in Gephi, Cytoscape and yEd
+    pub fn save_graph_to_graphml(&self, filename: &str) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        formats::graphml::write(&self.graph, &self.labels, &mut writer)?;
+        writer.flush()
+    }
+
+    // GEXF with the same attributes as `save_graph_to_graphml`, Gephi's native format
+    pub fn save_graph_to_gexf(&self, filename: &str) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(filename)?);
+        formats::gexf::write(&self.graph, &self.labels, &mut writer)?;
+        writer.flush()
+    }
+}
+
+// Read `files` in order into `builder`, attaching the file name to bad rows
+fn read_files<P: AsRef<Path>, W: EdgeWeight, Ty: EdgeType>(
+    builder: &mut GraphBuilder<'_, W, Ty>,
+    files: impl IntoIterator<Item = (P, GraphFormat)>,
+    options: &CsvOptions,
+) -> error::Result<Vec<RecordError>> {
+    let mut bad_rows = Vec::new();
+    for (filename, format) in files {
+        let filename = filename.as_ref();
+        let with_file = |mut error: RecordError| {
+            error.file = Some(filename.to_path_buf());
+            error
         };
+        match read_file(builder, filename, format, options) {
+            Ok(rows) => bad_rows.extend(rows.into_iter().map(with_file)),
+            Err(Error::Record(error)) => return Err(Error::Record(with_file(error))),
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(bad_rows)
+}
 
-        Command::new(opener).arg(output_image).status()?;
-        Ok(())
+fn read_file<W: EdgeWeight, Ty: EdgeType>(
+    builder: &mut GraphBuilder<'_, W, Ty>,
+    filename: &Path,
+    format: GraphFormat,
+    options: &CsvOptions,
+) -> error::Result<Vec<RecordError>> {
+    let compression = Compression::detect(filename)?;
+    let stream = Decompressed::open(filename, compression)?;
+    if format == GraphFormat::Csv {
+        return match stream {
+            Some(stream) => {
+                ingest::extend_from_reader(builder, stream, options, ingest::BLOCK_BYTES)
+            }
+            None => {
+                let bytes = std::fs::read(filename)?;
+                ingest::extend_from_bytes(builder, &bytes, options)
+            }
+        };
     }
+
+    let text = match stream {
+        Some(mut stream) => {
+            let mut text = String::new();
+            stream.read_to_string(&mut text)?;
+            text
+        }
+        None => std::fs::read_to_string(filename)?,
+    };
+    let imported = formats::parse(format, &text)?;
+    ingest::extend_from_imported(builder, imported, options)
 }
 
+// HSV fill color of a community in DOT output
+fn community_color(community_id: usize) -> String {
+    let hue = (community_id * 60) % 360;
+    format!("{:.1} 0.5 0.7", hue as f32)
+}
@@ -14,11 +14,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     CommunityDetector::render_and_open_graph("graph.dot", "graph.png")?;
 
     // 4. Print community info
-    let communities = detector.get_communities();
+    let mut communities: Vec<_> = detector.get_communities().into_iter().collect();
+    communities.sort_unstable_by_key(|&(id, _)| id);
     println!("Detected {} communities:", communities.len());
     for (id, members) in communities {
         println!("Community {} ({} members)", id, members.len());
     }
 
+    // 5. Export results for downstream consumers
+    detector.export_assignments("communities.csv", ExportFormat::Csv)?;
+    detector.export_community_summaries("community_summaries.jsonl", ExportFormat::JsonLines)?;
+    detector.save_quotient_graph_to_dot("communities.dot")?;
+
     Ok(())
-}
\ No newline at end of file
+}
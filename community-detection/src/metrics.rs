@@ -0,0 +1,146 @@
+use crate::algorithms::WeightedAdjacency;
+use crate::weight::EdgeWeight;
+use petgraph::{EdgeType, Graph};
+use std::collections::HashMap;
+
+// Quality of a single community within a partition.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommunityQuality {
+    pub community_id: usize,
+    pub size: usize,
+    // Total weight of edges with both endpoints in the community
+    pub internal_weight: f64,
+    // Total weight of edges leaving the community
+    pub boundary_weight: f64,
+    // Sum of the weighted degrees of the members
+    pub volume: f64,
+    // boundary_weight / min(volume, total volume - volume), 0 for isolated communities
+    pub conductance: f64,
+}
+
+// Partition quality report, so that different algorithms can be compared on
+// the same graph. Edge direction is ignored throughout.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartitionQuality {
+    pub modularity: f64,
+    // Fraction of the total edge weight that falls inside communities
+    pub coverage: f64,
+    // Fraction of node pairs that are correctly classified: linked pairs in
+    // the same community plus unlinked pairs in different communities
+    pub performance: f64,
+    pub intra_weight: f64,
+    pub inter_weight: f64,
+    // intra_weight / inter_weight, infinite when no edge crosses communities
+    pub intra_inter_ratio: f64,
+    // One entry per community, ordered by community id
+    pub communities: Vec<CommunityQuality>,
+}
+
+// Compute quality metrics for `labels` on `graph`. Nodes missing from
+// `labels` are treated as singleton communities.
+pub fn partition_quality<W: EdgeWeight, Ty: EdgeType>(
+    graph: &Graph<String, W, Ty>,
+    labels: &HashMap<String, usize>,
+) -> PartitionQuality {
+    let adjacency = WeightedAdjacency::from_graph(graph);
+
+    let mut ids: Vec<usize> = labels.values().copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+    let mut index: HashMap<usize, usize> = ids.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+    let mut next_id = ids.last().map_or(0, |&id| id + 1);
+    let membership: Vec<usize> = graph
+        .node_indices()
+        .map(|node| match labels.get(&graph[node]) {
+            Some(id) => index[id],
+            None => {
+                ids.push(next_id);
+                index.insert(next_id, ids.len() - 1);
+                next_id += 1;
+                ids.len() - 1
+            }
+        })
+        .collect();
+
+    let mut communities: Vec<CommunityQuality> = ids
+        .iter()
+        .map(|&community_id| CommunityQuality {
+            community_id,
+            size: 0,
+            internal_weight: 0.0,
+            boundary_weight: 0.0,
+            volume: 0.0,
+            conductance: 0.0,
+        })
+        .collect();
+
+    let mut linked_intra_pairs = 0usize;
+    let mut linked_pairs = 0usize;
+    for (node, &community) in membership.iter().enumerate() {
+        communities[community].size += 1;
+        communities[community].volume += adjacency.degrees[node];
+        communities[community].internal_weight += adjacency.self_loops[node];
+
+        for &(other, weight) in &adjacency.neighbors[node] {
+            if membership[other] == community {
+                if node < other {
+                    communities[community].internal_weight += weight;
+                    linked_intra_pairs += 1;
+                }
+            } else {
+                communities[community].boundary_weight += weight;
+            }
+            if node < other {
+                linked_pairs += 1;
+            }
+        }
+    }
+
+    let total_volume = adjacency.total_weight;
+    let total_weight = total_volume / 2.0;
+    let mut intra_weight = 0.0;
+    let mut modularity = 0.0;
+    for stats in communities.iter_mut() {
+        intra_weight += stats.internal_weight;
+        let denominator = stats.volume.min(total_volume - stats.volume);
+        if denominator > 0.0 {
+            stats.conductance = stats.boundary_weight / denominator;
+        }
+        if total_weight > 0.0 {
+            modularity +=
+                stats.internal_weight / total_weight - (stats.volume / total_volume).powi(2);
+        }
+    }
+    let inter_weight = total_weight - intra_weight;
+
+    let node_count = membership.len();
+    let total_pairs = node_count * node_count.saturating_sub(1) / 2;
+    let intra_pairs: usize = communities
+        .iter()
+        .map(|stats| stats.size * stats.size.saturating_sub(1) / 2)
+        .sum();
+    let linked_inter_pairs = linked_pairs - linked_intra_pairs;
+    let unlinked_inter_pairs = total_pairs - intra_pairs - linked_inter_pairs;
+
+    PartitionQuality {
+        modularity,
+        coverage: ratio(intra_weight, total_weight),
+        performance: ratio(
+            (linked_intra_pairs + unlinked_inter_pairs) as f64,
+            total_pairs as f64,
+        ),
+        intra_weight,
+        inter_weight,
+        intra_inter_ratio: intra_weight / inter_weight,
+        communities,
+    }
+}
+
+fn ratio(numerator: f64, denominator: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        0.0
+    }
+}
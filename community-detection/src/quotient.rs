@@ -0,0 +1,79 @@
+use crate::weight::EdgeWeight;
+use petgraph::graph::NodeIndex;
+use petgraph::{EdgeType, Graph};
+use std::collections::HashMap;
+use std::fmt;
+
+// A node of the quotient graph: one community and how many users it has.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommunityNode {
+    pub community_id: usize,
+    pub size: usize,
+}
+
+impl fmt::Display for CommunityNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let users = if self.size == 1 { "user" } else { "users" };
+        write!(
+            f,
+            "community {} ({} {})",
+            self.community_id, self.size, users
+        )
+    }
+}
+
+// Collapse every community in `labels` into a single node, ordered by
+// community id. The edge between two communities carries the summed weight
+// of the interactions between their members, saturating at `W::MAX`, and
+// interactions within a community become a self-loop. Users without a label
+// are left out.
+pub fn quotient_graph<W: EdgeWeight, Ty: EdgeType>(
+    graph: &Graph<String, W, Ty>,
+    labels: &HashMap<String, usize>,
+) -> Graph<CommunityNode, W, Ty> {
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    for &community_id in labels.values() {
+        *sizes.entry(community_id).or_default() += 1;
+    }
+    let mut communities: Vec<(usize, usize)> = sizes.into_iter().collect();
+    communities.sort_unstable();
+
+    let mut quotient = Graph::with_capacity(communities.len(), 0);
+    let nodes: HashMap<usize, NodeIndex> = communities
+        .into_iter()
+        .map(|(community_id, size)| {
+            let node = quotient.add_node(CommunityNode { community_id, size });
+            (community_id, node)
+        })
+        .collect();
+
+    let community_of: Vec<Option<NodeIndex>> = graph
+        .node_weights()
+        .map(|user| labels.get(user).map(|id| nodes[id]))
+        .collect();
+    let mut weights: HashMap<(NodeIndex, NodeIndex), W> = HashMap::new();
+    for edge in graph.raw_edges() {
+        let (Some(a), Some(b)) = (
+            community_of[edge.source().index()],
+            community_of[edge.target().index()],
+        ) else {
+            continue;
+        };
+        let key = if Ty::is_directed() {
+            (a, b)
+        } else {
+            (a.min(b), a.max(b))
+        };
+        weights
+            .entry(key)
+            .and_modify(|total| *total = total.checked_add(edge.weight).unwrap_or(W::MAX))
+            .or_insert(edge.weight);
+    }
+
+    let mut edges: Vec<((NodeIndex, NodeIndex), W)> = weights.into_iter().collect();
+    edges.sort_unstable_by_key(|&(key, _)| key);
+    for ((a, b), weight) in edges {
+        quotient.add_edge(a, b, weight);
+    }
+    quotient
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+
+// Numeric type stored on the edges of an interaction graph. Implemented for
+// the unsigned integers and the floats; detection algorithms work on the
+// `f64` value of every weight.
+pub trait EdgeWeight:
+    Copy + Default + PartialOrd + fmt::Debug + fmt::Display + Send + Sync + 'static
+{
+    // Largest representable weight, used when saturating
+    const MAX: Self;
+
+    // Whether the type holds fractional weights
+    const FLOAT: bool;
+
+    // Convert a parsed number, or `None` if it cannot be represented.
+    // Integer weights round to the nearest whole number.
+    fn from_f64(value: f64) -> Option<Self>;
+
+    fn to_f64(self) -> f64;
+
+    // Addition that returns `None` instead of overflowing
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! integer_weight {
+    ($($ty:ty),*) => {$(
+        impl EdgeWeight for $ty {
+            const MAX: Self = <$ty>::MAX;
+            const FLOAT: bool = false;
+
+            fn from_f64(value: f64) -> Option<Self> {
+                let value = value.round();
+                if value >= 0.0 && value <= <$ty>::MAX as f64 {
+                    Some(value as $ty)
+                } else {
+                    None
+                }
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$ty>::checked_add(self, other)
+            }
+        }
+    )*};
+}
+
+macro_rules! float_weight {
+    ($($ty:ty),*) => {$(
+        impl EdgeWeight for $ty {
+            const MAX: Self = <$ty>::MAX;
+            const FLOAT: bool = true;
+
+            fn from_f64(value: f64) -> Option<Self> {
+                let value = value as $ty;
+                value.is_finite().then_some(value)
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                let total = self + other;
+                total.is_finite().then_some(total)
+            }
+        }
+    )*};
+}
+
+integer_weight!(u32, u64);
+float_weight!(f32, f64);
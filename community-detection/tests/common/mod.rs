@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_FILE: AtomicUsize = AtomicUsize::new(0);
+
+// Path in the temp directory that no other call, test or process gets. The
+// name goes last so its extension still selects the format.
+pub fn temp_file(name: &str) -> PathBuf {
+    let id = NEXT_FILE.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("{}-{}-{}", std::process::id(), id, name))
+}
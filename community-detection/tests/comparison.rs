@@ -0,0 +1,121 @@
+use community_detection::{
+    Error, adjusted_rand_index, compare_partitions, load_partition_csv,
+    normalized_mutual_information, variation_of_information,
+};
+use std::collections::HashMap;
+
+mod common;
+use common::temp_file;
+
+fn partition(assignments: &[(&str, usize)]) -> HashMap<String, usize> {
+    assignments
+        .iter()
+        .map(|&(user, id)| (user.to_string(), id))
+        .collect()
+}
+
+#[test]
+fn identical_partitions_agree_up_to_renaming() {
+    let labels = partition(&[("a", 0), ("b", 0), ("c", 1), ("d", 1)]);
+    let reference = partition(&[("a", 7), ("b", 7), ("c", 3), ("d", 3)]);
+
+    let comparison = compare_partitions(&labels, &reference);
+    assert!((comparison.nmi - 1.0).abs() < 1e-12);
+    assert!((comparison.ari - 1.0).abs() < 1e-12);
+    assert!(comparison.vi.abs() < 1e-12);
+}
+
+#[test]
+fn partitions_without_shared_users_are_not_comparable() {
+    let labels = partition(&[("a", 0), ("b", 1)]);
+    let reference = partition(&[("c", 0), ("d", 1)]);
+
+    let comparison = compare_partitions(&labels, &reference);
+    assert!(comparison.nmi.is_nan());
+    assert!(comparison.ari.is_nan());
+    assert!(comparison.vi.is_nan());
+}
+
+#[test]
+fn numeric_community_ids_are_kept() {
+    let path = temp_file("numeric_partition.csv");
+    std::fs::write(&path, "user,community_id\nalice,4\nbob,2\ncarol,4\n").unwrap();
+
+    let labels = load_partition_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        labels.unwrap(),
+        partition(&[("alice", 4), ("bob", 2), ("carol", 4)])
+    );
+}
+
+#[test]
+fn community_names_are_numbered_by_first_appearance() {
+    let path = temp_file("named_partition.csv");
+    std::fs::write(
+        &path,
+        "user,community\nalice,red\nbob,blue\ncarol,red\ndave,3\n",
+    )
+    .unwrap();
+
+    let labels = load_partition_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        labels.unwrap(),
+        partition(&[("alice", 0), ("bob", 1), ("carol", 0), ("dave", 2)])
+    );
+}
+
+#[test]
+fn missing_community_column_is_an_error() {
+    let path = temp_file("one_column_partition.csv");
+    std::fs::write(&path, "user\nalice\n").unwrap();
+
+    let result = load_partition_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    match result {
+        Err(Error::Record(error)) => assert_eq!(error.line, 2),
+        other => panic!("expected a record error, got {:?}", other),
+    }
+}
+
+#[test]
+fn partial_agreement_matches_hand_computed_scores() {
+    // Contingency table [[2, 1, 0], [0, 1, 2]]: the reference splits each
+    // detected community of three into a pair and a user shared with the
+    // other community. `z` has no reference and is ignored.
+    let labels = partition(&[
+        ("a", 0),
+        ("b", 0),
+        ("c", 0),
+        ("d", 1),
+        ("e", 1),
+        ("f", 1),
+        ("z", 1),
+    ]);
+    let reference = partition(&[("a", 5), ("b", 5), ("c", 6), ("d", 6), ("e", 7), ("f", 7)]);
+
+    let (ln2, ln3) = (2f64.ln(), 3f64.ln());
+    // H(labels) = ln 2, H(reference) = ln 3, I = 2/3 ln 2
+    let nmi = (4.0 / 3.0) * ln2 / (ln2 + ln3);
+    // Pairs together in both: 2; in labels: 6; in reference: 3; of 15
+    let ari = (2.0 - 6.0 * 3.0 / 15.0) / ((6.0 + 3.0) / 2.0 - 6.0 * 3.0 / 15.0);
+    let vi = ln3 - ln2 / 3.0;
+
+    for comparison in [
+        compare_partitions(&labels, &reference),
+        compare_partitions(&reference, &labels),
+    ] {
+        assert!((comparison.nmi - nmi).abs() < 1e-12, "{:?}", comparison);
+        assert!(
+            (comparison.ari - 8.0 / 33.0).abs() < 1e-12,
+            "{:?}",
+            comparison
+        );
+        assert!((comparison.ari - ari).abs() < 1e-12);
+        assert!((comparison.vi - vi).abs() < 1e-12, "{:?}", comparison);
+    }
+    assert!((normalized_mutual_information(&labels, &reference) - nmi).abs() < 1e-12);
+    assert!((adjusted_rand_index(&labels, &reference) - ari).abs() < 1e-12);
+    assert!((variation_of_information(&labels, &reference) - vi).abs() < 1e-12);
+}
@@ -0,0 +1,113 @@
+#![cfg(all(feature = "gzip", feature = "zstd"))]
+
+use community_detection::{CommunityDetector, Compression, CsvOptions, GraphFormat};
+use flate2::write::GzEncoder;
+use petgraph::Directed;
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+mod common;
+use common::temp_file;
+
+const INPUT: &str = "source,target,weight\nalice,bob,3\nbob,carol,1\nalice,bob,2\ncarol,alice,4\n";
+
+fn gzip(bytes: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zstd(bytes: &[u8]) -> Vec<u8> {
+    zstd::encode_all(bytes, 0).unwrap()
+}
+
+fn edges(detector: &CommunityDetector<Directed>) -> HashMap<(String, String), u32> {
+    let graph = &detector.graph;
+    graph
+        .edge_references()
+        .map(|edge| {
+            let key = (graph[edge.source()].clone(), graph[edge.target()].clone());
+            (key, *edge.weight())
+        })
+        .collect()
+}
+
+fn load(path: &Path, format: GraphFormat) -> HashMap<(String, String), u32> {
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_file(path, format, &CsvOptions::default());
+    std::fs::remove_file(path).unwrap();
+    result.unwrap();
+    edges(&detector)
+}
+
+// `INPUT` loaded without going through a file
+fn expected() -> HashMap<(String, String), u32> {
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(INPUT.as_bytes(), &CsvOptions::default())
+        .unwrap();
+    edges(&detector)
+}
+
+#[test]
+fn gzip_round_trip() {
+    let path = temp_file("interactions.csv.gz");
+    std::fs::write(&path, gzip(INPUT.as_bytes())).unwrap();
+    assert_eq!(Compression::detect(&path).unwrap(), Compression::Gzip);
+    assert_eq!(load(&path, GraphFormat::Csv), expected());
+}
+
+#[test]
+fn zstd_round_trip() {
+    let path = temp_file("interactions.csv.zst");
+    std::fs::write(&path, zstd(INPUT.as_bytes())).unwrap();
+    assert_eq!(Compression::detect(&path).unwrap(), Compression::Zstd);
+    assert_eq!(load(&path, GraphFormat::Csv), expected());
+}
+
+#[test]
+fn compression_is_detected_without_extension() {
+    let path = temp_file("interactions-gzip");
+    std::fs::write(&path, gzip(INPUT.as_bytes())).unwrap();
+    assert_eq!(Compression::detect(&path).unwrap(), Compression::Gzip);
+    assert_eq!(load(&path, GraphFormat::Csv), expected());
+
+    let path = temp_file("interactions-zstd");
+    std::fs::write(&path, zstd(INPUT.as_bytes())).unwrap();
+    assert_eq!(Compression::detect(&path).unwrap(), Compression::Zstd);
+    assert_eq!(load(&path, GraphFormat::Csv), expected());
+}
+
+#[test]
+fn concatenated_gzip_members_are_read_whole() {
+    let (head, tail) = INPUT.split_at(INPUT.find("alice,bob,2").unwrap());
+    let mut bytes = gzip(head.as_bytes());
+    bytes.extend(gzip(tail.as_bytes()));
+    let path = temp_file("concatenated.csv.gz");
+    std::fs::write(&path, bytes).unwrap();
+    assert_eq!(load(&path, GraphFormat::Csv), expected());
+}
+
+#[test]
+fn other_formats_are_decompressed() {
+    let path = temp_file("interactions.txt.zst");
+    std::fs::write(&path, zstd(b"# comment\nalice bob 5\nbob carol\n")).unwrap();
+    let edges = load(&path, GraphFormat::EdgeList);
+    assert_eq!(edges[&("alice".to_string(), "bob".to_string())], 5);
+    assert_eq!(edges[&("bob".to_string(), "carol".to_string())], 1);
+}
+
+#[test]
+fn truncated_input_is_an_error() {
+    let bytes = gzip(INPUT.as_bytes());
+    let path = temp_file("truncated.csv.gz");
+    std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_file(&path, GraphFormat::Csv, &CsvOptions::default());
+    std::fs::remove_file(&path).unwrap();
+    assert!(result.is_err());
+    assert_eq!(detector.graph.node_count(), 0);
+}
@@ -0,0 +1,181 @@
+use community_detection::{
+    CommunityAlgorithm, CommunityDetector, CsvOptions, Error, Partition, PlantedPartitionConfig,
+    RecordError, RecordErrorKind, UndirectedCommunityDetector, generate_planted_partition_csv,
+    load_partition_csv,
+};
+use petgraph::graph::NodeIndex;
+use petgraph::{Directed, EdgeType, Graph};
+use std::collections::{BTreeMap, HashMap};
+
+mod common;
+use common::temp_file;
+
+// Detector loaded with a planted partition graph, and the planted blocks
+fn planted(name: &str, seed: u64) -> (CommunityDetector, HashMap<String, usize>) {
+    let path = temp_file(&format!("{}-{}.csv", name, seed));
+    let truth = temp_file(&format!("{}-{}-truth.csv", name, seed));
+    let config = PlantedPartitionConfig {
+        num_blocks: 5,
+        users_per_block: 30,
+        intra_probability: 0.3,
+        inter_probability: 0.01,
+        seed: Some(seed),
+        ..PlantedPartitionConfig::default()
+    };
+    generate_planted_partition_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap())
+        .unwrap();
+
+    let detector = CommunityDetector::from_csv(path.to_str().unwrap());
+    let reference = load_partition_csv(truth.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&truth).unwrap();
+    (detector.unwrap(), reference.unwrap())
+}
+
+#[test]
+fn louvain_recovers_planted_partition() {
+    for seed in 0..5 {
+        let (mut detector, reference) = planted("louvain", seed);
+        detector.detect_communities_louvain();
+
+        assert_eq!(detector.labels.len(), reference.len());
+        assert_eq!(detector.get_communities().len(), 5, "seed {}", seed);
+        let comparison = detector.compare_with(&reference);
+        assert!(comparison.nmi > 0.95, "seed {}: {:?}", seed, comparison);
+        assert!(detector.quality().modularity > 0.6, "seed {}", seed);
+    }
+}
+
+#[test]
+fn label_propagation_recovers_planted_partition() {
+    for seed in 0..5 {
+        let (mut detector, reference) = planted("label_propagation", seed);
+        detector.detect_communities_label_propagation(seed, 100);
+
+        assert_eq!(detector.labels.len(), reference.len());
+        let comparison = detector.compare_with(&reference);
+        assert!(comparison.nmi > 0.9, "seed {}: {:?}", seed, comparison);
+    }
+}
+
+#[test]
+fn label_propagation_is_reproducible() {
+    let (mut first, _) = planted("label_propagation_repeat", 11);
+    let (mut second, _) = planted("label_propagation_repeat", 11);
+    first.detect_communities_label_propagation(3, 100);
+    second.detect_communities_label_propagation(3, 100);
+    assert_eq!(first.labels, second.labels);
+}
+
+#[test]
+fn node_list_users_become_singletons() {
+    let nodes = temp_file("nodes.csv");
+    std::fs::write(&nodes, "user,community\nb,0\nx,1\ny,1\nx,1\n").unwrap();
+
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(
+            "source,target,weight\na,b,3\nb,a,1\nb,c,2\nc,b,2\n".as_bytes(),
+            &CsvOptions::default(),
+        )
+        .unwrap();
+    let added = detector.add_nodes_from_csv(nodes.to_str().unwrap());
+    std::fs::remove_file(&nodes).unwrap();
+    assert_eq!(added.unwrap(), 2);
+    assert_eq!(detector.graph.node_count(), 5);
+
+    let algorithms: [fn(&mut CommunityDetector); 4] = [
+        CommunityDetector::detect_communities,
+        CommunityDetector::detect_communities_louvain,
+        CommunityDetector::detect_communities_leiden,
+        |detector| detector.detect_communities_label_propagation(1, 20),
+    ];
+    for detect in algorithms {
+        detect(&mut detector);
+        assert_eq!(detector.labels.len(), 5);
+        let communities = detector.get_communities();
+        for user in ["x", "y"] {
+            assert_eq!(communities[&detector.labels[user]], [user]);
+        }
+        assert_eq!(detector.labels["a"], detector.labels["b"]);
+    }
+}
+
+#[test]
+fn node_list_needs_a_user_column() {
+    let nodes = temp_file("bad_nodes.csv");
+    std::fs::write(&nodes, "user\nx\n\"\"\n").unwrap();
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.add_nodes_from_csv(nodes.to_str().unwrap());
+    std::fs::remove_file(&nodes).unwrap();
+    assert!(matches!(
+        result,
+        Err(Error::Record(RecordError {
+            line: 3,
+            kind: RecordErrorKind::MissingColumn(0),
+            ..
+        }))
+    ));
+}
+
+// Groups users by the first letter of their name, ignoring the edges
+struct FirstLetter;
+
+impl<Ty: EdgeType> CommunityAlgorithm<Ty> for FirstLetter {
+    fn detect(&self, graph: &Graph<String, u32, Ty>) -> Partition {
+        let mut groups: BTreeMap<char, Vec<NodeIndex>> = BTreeMap::new();
+        for node in graph.node_indices() {
+            let letter = graph[node].chars().next().unwrap();
+            groups.entry(letter).or_default().push(node);
+        }
+        groups.into_values().collect()
+    }
+}
+
+#[test]
+fn user_defined_algorithms_plug_in() {
+    let input = "source,target,weight\nann,bob,1\nbea,amy,1\nbo,carl,1\n";
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &CsvOptions::default())
+        .unwrap();
+
+    detector.detect_communities_with(&FirstLetter);
+    let expected: HashMap<String, usize> = [
+        ("bob", 0),
+        ("bea", 0),
+        ("bo", 0),
+        ("ann", 1),
+        ("amy", 1),
+        ("carl", 2),
+    ]
+    .into_iter()
+    .map(|(user, id)| (user.to_string(), id))
+    .collect();
+    assert_eq!(detector.labels, expected);
+
+    // Trait objects and closures work as well
+    let algorithm: &dyn CommunityAlgorithm = &FirstLetter;
+    detector.labels.clear();
+    detector.detect_communities_with(algorithm);
+    assert_eq!(detector.labels, expected);
+
+    detector.detect_communities_with(&|graph: &Graph<String, u32>| -> Partition {
+        vec![graph.node_indices().collect()]
+    });
+    assert_eq!(detector.get_communities().len(), 1);
+    assert!(detector.labels.values().all(|&id| id == 0));
+}
+
+#[test]
+fn user_defined_algorithms_run_on_undirected_graphs() {
+    let mut detector = UndirectedCommunityDetector::default();
+    detector
+        .extend_from_reader(
+            "source,target,weight\nann,bob,1\nbob,ann,1\n".as_bytes(),
+            &CsvOptions::default(),
+        )
+        .unwrap();
+    detector.detect_communities_with(&FirstLetter);
+    assert_eq!(detector.get_communities().len(), 2);
+}
@@ -0,0 +1,82 @@
+use community_detection::{
+    PlantedPartitionConfig, UsernameGenerator, generate_interaction_csv_seeded,
+    generate_planted_partition_csv,
+};
+use rayon::ThreadPoolBuilder;
+
+mod common;
+use common::temp_file;
+
+// Run `generate` on a pool with `threads` threads and return what it wrote
+fn generate_with_threads(
+    threads: usize,
+    name: &str,
+    generate: impl Fn(&str) + Send + Sync,
+) -> Vec<u8> {
+    let path = temp_file(&format!("{}-{}", threads, name));
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .unwrap();
+    pool.install(|| generate(path.to_str().unwrap()));
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    bytes
+}
+
+#[test]
+fn interactions_do_not_depend_on_thread_count() {
+    let generate =
+        |filename: &str| generate_interaction_csv_seeded(500, 50_000, filename, 42).unwrap();
+    let single = generate_with_threads(1, "interactions.csv", generate);
+    let parallel = generate_with_threads(4, "interactions.csv", generate);
+    assert!(!single.is_empty());
+    assert_eq!(single, parallel);
+}
+
+#[test]
+fn planted_partition_does_not_depend_on_thread_count() {
+    let config = PlantedPartitionConfig {
+        seed: Some(42),
+        ..PlantedPartitionConfig::default()
+    };
+    let generate = |filename: &str| {
+        let truth = format!("{}.truth", filename);
+        generate_planted_partition_csv(&config, filename, &truth).unwrap();
+        std::fs::remove_file(truth).unwrap();
+    };
+    let single = generate_with_threads(1, "planted.csv", generate);
+    let parallel = generate_with_threads(4, "planted.csv", generate);
+    assert_eq!(single, parallel);
+}
+
+#[test]
+fn usernames_do_not_depend_on_thread_count() {
+    let generate = |threads: usize| {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        pool.install(|| {
+            UsernameGenerator::new()
+                .generate_unique_batch_seeded(5000, 7)
+                .unwrap()
+        })
+    };
+    assert_eq!(generate(1), generate(4));
+}
+
+#[test]
+fn used_names_carry_over_between_batches() {
+    let generator = UsernameGenerator::new();
+    let first = generator.generate_unique_batch_seeded(100, 7).unwrap();
+    let second = generator.generate_unique_batch_seeded(100, 7).unwrap();
+    assert_ne!(first, second);
+    assert!(second.iter().all(|name| !first.contains(name)));
+    assert_eq!(
+        UsernameGenerator::new()
+            .generate_unique_batch_seeded(100, 7)
+            .unwrap(),
+        first
+    );
+}
@@ -0,0 +1,369 @@
+use community_detection::{
+    CommunityDetector, CsvOptions, ExportFormat, GraphFormat, WeightFormat, load_partition_csv,
+};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, EdgeType, Undirected};
+use std::collections::HashMap;
+use std::path::Path;
+
+mod common;
+use common::temp_file;
+
+fn detector(rows: &str) -> CommunityDetector {
+    let mut detector = CommunityDetector::<Directed>::default();
+    let input = format!("source,target,weight\n{}", rows);
+    detector
+        .extend_from_reader(input.as_bytes(), &Default::default())
+        .unwrap();
+    detector
+}
+
+#[test]
+fn dot_output_includes_users_without_a_community() {
+    let mut detector = detector("alice,bob,3\nbob,alice,1\n");
+    detector.detect_communities();
+    detector.add_nodes(["carol"]);
+
+    let path = temp_file("unlabelled.dot");
+    detector.save_graph_to_dot(path.to_str().unwrap()).unwrap();
+    let dot = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let carol = dot.lines().find(|line| line.contains("\"carol\"")).unwrap();
+    assert!(carol.contains("style=dashed"));
+    let alice = dot.lines().find(|line| line.contains("\"alice\"")).unwrap();
+    assert!(alice.contains("style=filled"));
+}
+
+// Two communities joined by the `b,c` edge
+fn labelled_detector() -> CommunityDetector {
+    let mut detector = detector("a,b,3\nb,c,1\nc,d,2\n");
+    detector.labels = [("a", 0), ("b", 0), ("c", 1), ("d", 1)]
+        .into_iter()
+        .map(|(user, id)| (user.to_string(), id))
+        .collect();
+    detector
+}
+
+// Rows of a Parquet file as read by the `parquet` crate
+fn read_parquet(path: &Path) -> (Vec<String>, Vec<Vec<Field>>) {
+    let reader = SerializedFileReader::new(std::fs::File::open(path).unwrap()).unwrap();
+    let schema = reader.metadata().file_metadata().schema_descr();
+    let columns = schema
+        .columns()
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect();
+    let rows = reader
+        .get_row_iter(None)
+        .unwrap()
+        .map(|row| {
+            row.unwrap()
+                .get_column_iter()
+                .map(|(_, field)| field.clone())
+                .collect()
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn export(name: &str, write: impl FnOnce(&str) -> std::io::Result<()>) -> String {
+    let path = temp_file(name);
+    write(path.to_str().unwrap()).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    contents
+}
+
+#[test]
+fn assignments_csv_reads_back() {
+    let mut detector = labelled_detector();
+    detector.labels.insert("e, \"the\" user".to_string(), 1);
+    let path = temp_file("assignments.csv");
+    detector
+        .export_assignments(path.to_str().unwrap(), ExportFormat::Csv)
+        .unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let loaded = load_partition_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        contents,
+        "user,community_id\na,0\nb,0\nc,1\nd,1\n\"e, \"\"the\"\" user\",1\n"
+    );
+    assert_eq!(loaded.unwrap(), detector.labels);
+}
+
+#[test]
+fn assignments_json_lines() {
+    let mut detector = labelled_detector();
+    detector.labels.insert("q\"\\\n".to_string(), 2);
+    let contents = export("assignments.jsonl", |filename| {
+        detector.export_assignments(filename, ExportFormat::JsonLines)
+    });
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            r#"{"user":"a","community_id":0}"#,
+            r#"{"user":"b","community_id":0}"#,
+            r#"{"user":"c","community_id":1}"#,
+            r#"{"user":"d","community_id":1}"#,
+            r#"{"user":"q\"\\\n","community_id":2}"#,
+        ]
+    );
+}
+
+#[test]
+fn summaries_csv_and_json_lines() {
+    let detector = labelled_detector();
+    let csv = export("summaries.csv", |filename| {
+        detector.export_community_summaries(filename, ExportFormat::Csv)
+    });
+    assert_eq!(
+        csv,
+        "community_id,size,internal_weight,boundary_weight,volume,conductance\n\
+         0,2,3,1,7,0.2\n\
+         1,2,2,1,5,0.2\n"
+    );
+
+    let json = export("summaries.jsonl", |filename| {
+        detector.export_community_summaries(filename, ExportFormat::JsonLines)
+    });
+    assert_eq!(
+        json.lines().next().unwrap(),
+        r#"{"community_id":0,"size":2,"internal_weight":3,"boundary_weight":1,"volume":7,"conductance":0.2}"#
+    );
+}
+
+#[test]
+fn assignments_parquet_reads_back() {
+    // More rows than fit in one data page
+    let detector = CommunityDetector::<Directed> {
+        labels: (0..150_000)
+            .map(|i| (format!("user{:06}", i), i % 7))
+            .collect(),
+        ..CommunityDetector::default()
+    };
+    let path = temp_file("assignments.parquet");
+    detector
+        .export_assignments(path.to_str().unwrap(), ExportFormat::Parquet)
+        .unwrap();
+    let (columns, rows) = read_parquet(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(columns, ["user", "community_id"]);
+    assert_eq!(rows.len(), detector.labels.len());
+    let mut loaded = HashMap::new();
+    let mut previous = (0, String::new());
+    for row in rows {
+        let [Field::Str(user), Field::Long(id)] = &row[..] else {
+            panic!("unexpected row {:?}", row);
+        };
+        let key = (*id as usize, user.clone());
+        assert!(key > previous, "rows are ordered by community and name");
+        previous = key.clone();
+        loaded.insert(key.1, key.0);
+    }
+    assert_eq!(loaded, detector.labels);
+}
+
+#[test]
+fn summaries_parquet_reads_back() {
+    let detector = labelled_detector();
+    let path = temp_file("summaries.parquet");
+    detector
+        .export_community_summaries(path.to_str().unwrap(), ExportFormat::Parquet)
+        .unwrap();
+    let (columns, rows) = read_parquet(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        columns,
+        [
+            "community_id",
+            "size",
+            "internal_weight",
+            "boundary_weight",
+            "volume",
+            "conductance"
+        ]
+    );
+    assert_eq!(
+        rows,
+        [
+            vec![
+                Field::Long(0),
+                Field::Long(2),
+                Field::Double(3.0),
+                Field::Double(1.0),
+                Field::Double(7.0),
+                Field::Double(0.2)
+            ],
+            vec![
+                Field::Long(1),
+                Field::Long(2),
+                Field::Double(2.0),
+                Field::Double(1.0),
+                Field::Double(5.0),
+                Field::Double(0.2)
+            ],
+        ]
+    );
+}
+
+#[test]
+fn export_format_from_extension() {
+    let format = |name: &str| ExportFormat::from_path(Path::new(name));
+    assert_eq!(format("out.CSV"), Some(ExportFormat::Csv));
+    assert_eq!(format("out.ndjson"), Some(ExportFormat::JsonLines));
+    assert_eq!(format("out.jsonl"), Some(ExportFormat::JsonLines));
+    assert_eq!(format("out.parquet"), Some(ExportFormat::Parquet));
+    assert_eq!(format("out.json"), None);
+}
+
+// Every edge of `detector` as `(source, target) -> weight`
+fn edge_map<Ty: EdgeType, W: Copy>(
+    detector: &CommunityDetector<Ty, W>,
+) -> HashMap<(String, String), W> {
+    let graph = &detector.graph;
+    graph
+        .edge_references()
+        .map(|edge| {
+            let key = (graph[edge.source()].clone(), graph[edge.target()].clone());
+            (key, *edge.weight())
+        })
+        .collect()
+}
+
+#[test]
+fn graphml_reads_back_into_the_same_graph() {
+    let mut detector = detector("a,b,3\nb,a,1\n<x & \"y\">,a,2\nc,c,4\n");
+    detector.add_nodes(["lonely"]);
+    detector.labels = [("a", 0), ("b", 0), ("<x & \"y\">", 1)]
+        .into_iter()
+        .map(|(user, id)| (user.to_string(), id))
+        .collect();
+
+    let path = temp_file("round_trip.graphml");
+    detector
+        .save_graph_to_graphml(path.to_str().unwrap())
+        .unwrap();
+    let graphml = std::fs::read_to_string(&path).unwrap();
+    let loaded = CommunityDetector::<Directed>::load_file(
+        path.to_str().unwrap(),
+        GraphFormat::GraphMl,
+        &CsvOptions::default(),
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    let (loaded, summary) = loaded.unwrap();
+    assert!(summary.is_empty());
+    assert_eq!(edge_map(&loaded), edge_map(&detector));
+    let names = |detector: &CommunityDetector| -> Vec<String> {
+        let mut names: Vec<String> = detector.graph.node_weights().cloned().collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(&loaded), names(&detector));
+
+    let node = |name: &str| {
+        graphml
+            .lines()
+            .find(|line| line.contains(&format!(r#"<node id="{}">"#, name)))
+            .unwrap()
+    };
+    assert!(node("a").contains(r#"<data key="community">0</data>"#));
+    assert!(node("a").contains(r#"<data key="degree">3</data>"#));
+    assert!(node("&lt;x &amp; &quot;y&quot;&gt;").contains(r#"<data key="community">1</data>"#));
+    assert!(!node("lonely").contains("community"));
+    assert!(node("lonely").contains(r#"<data key="degree">0</data>"#));
+}
+
+#[test]
+fn graphml_keeps_float_weights_and_undirected_edges() {
+    let options = CsvOptions {
+        weight_format: WeightFormat::Float,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Undirected, f64>::default();
+    detector
+        .extend_from_reader(
+            "source,target,weight\na,b,0.25\nb,a,0.5\nb,c,1.75\n".as_bytes(),
+            &options,
+        )
+        .unwrap();
+
+    let path = temp_file("undirected.graphml");
+    detector
+        .save_graph_to_graphml(path.to_str().unwrap())
+        .unwrap();
+    let loaded = CommunityDetector::<Undirected, f64>::load_file(
+        path.to_str().unwrap(),
+        GraphFormat::GraphMl,
+        &options,
+    );
+    let graphml = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(graphml.contains(r#"edgedefault="undirected""#));
+    let (loaded, _) = loaded.unwrap();
+    assert_eq!(loaded.graph.edge_count(), 2);
+    assert_eq!(edge_map(&loaded), edge_map(&detector));
+}
+
+#[test]
+fn gexf_lists_nodes_attributes_and_edges() {
+    let mut detector = labelled_detector();
+    detector.add_nodes(["e & f"]);
+    let gexf = export("graph.gexf", |path| detector.save_graph_to_gexf(path));
+
+    assert!(gexf.contains(r#"defaultedgetype="directed""#));
+    let ids: HashMap<String, String> = gexf
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim().strip_prefix(r#"<node id=""#)?;
+            let (id, rest) = line.split_once('"')?;
+            let label = rest.split(r#"label=""#).nth(1)?.split('"').next()?;
+            Some((id.to_string(), label.to_string()))
+        })
+        .collect();
+    assert_eq!(ids.len(), 5);
+    assert!(ids.values().any(|label| label == "e &amp; f"));
+
+    let node = |label: &str| {
+        gexf.lines()
+            .find(|line| line.contains(&format!(r#"label="{}""#, label)))
+            .unwrap()
+    };
+    assert!(node("a").contains(r#"<attvalue for="community" value="0"/>"#));
+    assert!(node("c").contains(r#"<attvalue for="community" value="1"/>"#));
+    assert!(node("b").contains(r#"<attvalue for="degree" value="2"/>"#));
+    assert!(!node("e &amp; f").contains(r#"for="community""#));
+
+    let mut edges: Vec<(String, String, String)> = gexf
+        .lines()
+        .filter(|line| line.trim().starts_with("<edge "))
+        .map(|line| {
+            let value = |name: &str| {
+                line.split(&format!(r#" {}=""#, name))
+                    .nth(1)
+                    .unwrap()
+                    .split('"')
+                    .next()
+                    .unwrap()
+            };
+            (
+                ids[value("source")].clone(),
+                ids[value("target")].clone(),
+                value("weight").to_string(),
+            )
+        })
+        .collect();
+    edges.sort();
+    let expected = [("a", "b", "3"), ("b", "c", "1"), ("c", "d", "2")]
+        .map(|(s, t, w)| (s.to_string(), t.to_string(), w.to_string()));
+    assert_eq!(edges, expected);
+}
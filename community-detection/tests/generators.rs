@@ -0,0 +1,163 @@
+use community_detection::{
+    CommunityDetector, LfrConfig, PlantedPartitionConfig, WeightDistribution, generate_lfr_csv,
+    generate_planted_partition_csv, load_partition_csv,
+};
+use petgraph::visit::EdgeRef;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+
+mod common;
+use common::temp_file;
+
+fn planted_partition_error(config: PlantedPartitionConfig) -> ErrorKind {
+    let path = temp_file("invalid_planted.csv");
+    let truth = temp_file("invalid_planted_truth.csv");
+    let result =
+        generate_planted_partition_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap());
+    assert!(!path.exists() && !truth.exists());
+    result.unwrap_err().kind()
+}
+
+#[test]
+fn planted_partition_rejects_invalid_probabilities() {
+    for probability in [-0.1, 1.5, f64::NAN] {
+        let config = PlantedPartitionConfig {
+            intra_probability: probability,
+            ..PlantedPartitionConfig::default()
+        };
+        assert_eq!(planted_partition_error(config), ErrorKind::InvalidInput);
+
+        let config = PlantedPartitionConfig {
+            inter_probability: probability,
+            ..PlantedPartitionConfig::default()
+        };
+        assert_eq!(planted_partition_error(config), ErrorKind::InvalidInput);
+    }
+}
+
+#[test]
+fn planted_partition_rejects_empty_weight_range() {
+    let config = PlantedPartitionConfig {
+        inter_weights: WeightDistribution::Uniform { min: 5, max: 1 },
+        ..PlantedPartitionConfig::default()
+    };
+    assert_eq!(planted_partition_error(config), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn lfr_rejects_empty_weight_range() {
+    let path = temp_file("invalid_lfr.csv");
+    let truth = temp_file("invalid_lfr_truth.csv");
+    let config = LfrConfig {
+        weights: WeightDistribution::Uniform { min: 2, max: 1 },
+        ..LfrConfig::default()
+    };
+    let result = generate_lfr_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap());
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn planted_partition_accepts_boundary_probabilities() {
+    let path = temp_file("boundary_planted.csv");
+    let truth = temp_file("boundary_planted_truth.csv");
+    let config = PlantedPartitionConfig {
+        num_blocks: 2,
+        users_per_block: 3,
+        intra_probability: 1.0,
+        inter_probability: 0.0,
+        intra_weights: WeightDistribution::Constant(2),
+        seed: Some(1),
+        ..PlantedPartitionConfig::default()
+    };
+    generate_planted_partition_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap())
+        .unwrap();
+
+    let rows = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&truth).unwrap();
+    // Both blocks are complete graphs on three users and nothing else
+    assert_eq!(rows.lines().count(), 1 + 2 * 3);
+    assert!(rows.lines().skip(1).all(|row| row.ends_with(",2")));
+}
+
+#[test]
+fn lfr_matches_the_requested_structure() {
+    for mixing in [0.1, 0.4] {
+        let path = temp_file("lfr.csv");
+        let truth = temp_file("lfr_truth.csv");
+        let config = LfrConfig {
+            mixing,
+            seed: Some(3),
+            ..LfrConfig::default()
+        };
+        generate_lfr_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap()).unwrap();
+        let detector = CommunityDetector::from_csv(path.to_str().unwrap());
+        let communities = load_partition_csv(truth.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&truth).unwrap();
+
+        // The ground truth covers exactly the users of the graph
+        let graph = detector.unwrap().graph;
+        let communities = communities.unwrap();
+        assert_eq!(communities.len(), config.num_users);
+        assert_eq!(graph.node_count(), config.num_users);
+        assert!(
+            graph
+                .node_weights()
+                .all(|user| communities.contains_key(user))
+        );
+
+        let mut sizes: HashMap<usize, usize> = HashMap::new();
+        for &community in communities.values() {
+            *sizes.entry(community).or_default() += 1;
+        }
+        let size_range = config.min_community..=config.max_community;
+        assert!(
+            sizes.values().all(|size| size_range.contains(size)),
+            "{:?}",
+            sizes
+        );
+
+        let mut degrees = vec![0; graph.node_count()];
+        let mut crossing = 0;
+        for edge in graph.edge_references() {
+            assert_ne!(edge.source(), edge.target());
+            degrees[edge.source().index()] += 1;
+            degrees[edge.target().index()] += 1;
+            if communities[&graph[edge.source()]] != communities[&graph[edge.target()]] {
+                crossing += 1;
+            }
+        }
+        assert!(degrees.iter().all(|&degree| degree <= config.max_degree));
+        let average = 2.0 * graph.edge_count() as f64 / graph.node_count() as f64;
+        assert!(
+            (average - config.average_degree).abs() < 0.05 * config.average_degree,
+            "average degree {}",
+            average
+        );
+        let realized = crossing as f64 / graph.edge_count() as f64;
+        assert!(
+            (realized - mixing).abs() < 0.03,
+            "mixing {} for {}",
+            realized,
+            mixing
+        );
+    }
+}
+
+#[test]
+fn lfr_rejects_sizes_that_cannot_add_up() {
+    let path = temp_file("impossible_lfr.csv");
+    let truth = temp_file("impossible_lfr_truth.csv");
+    let config = LfrConfig {
+        num_users: 101,
+        max_degree: 20,
+        average_degree: 5.0,
+        min_community: 20,
+        max_community: 20,
+        seed: Some(1),
+        ..LfrConfig::default()
+    };
+    let result = generate_lfr_csv(&config, path.to_str().unwrap(), truth.to_str().unwrap());
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+}
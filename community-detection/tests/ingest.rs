@@ -0,0 +1,645 @@
+use community_detection::{
+    Aggregation, BadRowPolicy, Column, CommunityDetector, CsvOptions, EdgeWeight, Error,
+    GraphFormat, OverflowPolicy, RecordError, RecordErrorKind, SelfLoopPolicy,
+    UndirectedCommunityDetector, WeightFormat,
+};
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, EdgeType};
+use std::collections::HashMap;
+
+mod common;
+use common::temp_file;
+
+// Edge weights by `(source, target)` name
+fn edges<Ty: EdgeType, W: Copy>(
+    detector: &CommunityDetector<Ty, W>,
+) -> HashMap<(String, String), W> {
+    let graph = &detector.graph;
+    graph
+        .edge_references()
+        .map(|edge| {
+            let key = (graph[edge.source()].clone(), graph[edge.target()].clone());
+            (key, *edge.weight())
+        })
+        .collect()
+}
+
+fn edge<W: Copy>(edges: &HashMap<(String, String), W>, source: &str, target: &str) -> W {
+    edges[&(source.to_string(), target.to_string())]
+}
+
+#[test]
+fn float_weights_load_with_default_options() {
+    let path = temp_file("float_weights.csv");
+    std::fs::write(&path, "source,target,weight\na,b,0.75\nb,c,2\na,b,0.5\n").unwrap();
+
+    let result = CommunityDetector::<Directed, f64>::load_csv(
+        path.to_str().unwrap(),
+        &CsvOptions::default(),
+    );
+    std::fs::remove_file(&path).unwrap();
+    let (detector, _) = result.unwrap();
+    let edges = edges(&detector);
+    assert_eq!(edge(&edges, "a", "b"), 1.25);
+    assert_eq!(edge(&edges, "b", "c"), 2.0);
+}
+
+#[test]
+fn integer_weights_reject_decimals_unless_float_format() {
+    let input = "source,target,weight\na,b,0.75\n";
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_reader(input.as_bytes(), &CsvOptions::default());
+    assert!(matches!(result, Err(Error::Record(error)) if error.line == 2));
+
+    let options = CsvOptions {
+        weight_format: WeightFormat::Float,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &options)
+        .unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), 1);
+}
+
+#[test]
+fn repeated_pairs_aggregate_across_files() {
+    let first = temp_file("aggregate_first.csv");
+    let second = temp_file("aggregate_second.csv");
+    std::fs::write(&first, "source,target,weight\na,b,1\na,b,1\nb,c,7\n").unwrap();
+    std::fs::write(&second, "source,target,weight\na,b,10\n").unwrap();
+
+    let mean = CsvOptions {
+        aggregation: Aggregation::Mean,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_files([&first, &second], &mean);
+    let last = CsvOptions {
+        aggregation: Aggregation::Last,
+        ..CsvOptions::default()
+    };
+    let mut latest = CommunityDetector::<Directed>::default();
+    let last_result = latest.extend_from_files([&first, &second], &last);
+    std::fs::remove_file(&first).unwrap();
+    std::fs::remove_file(&second).unwrap();
+
+    result.unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), 4);
+    last_result.unwrap();
+    assert_eq!(edge(&edges(&latest), "a", "b"), 10);
+}
+
+#[test]
+fn failing_file_adds_nothing() {
+    let good = temp_file("atomic_good.csv");
+    let bad = temp_file("atomic_bad.csv");
+    std::fs::write(&good, "source,target,weight\nc,d,1\na,b,2\n").unwrap();
+    std::fs::write(&bad, "source,target,weight\nd,e,1\ne,f,x\n").unwrap();
+
+    let mut detector = CommunityDetector::<Directed>::default();
+    let input = "source,target,weight\na,b,5\n";
+    detector
+        .extend_from_reader(input.as_bytes(), &CsvOptions::default())
+        .unwrap();
+    let result = detector.extend_from_files([&good, &bad], &CsvOptions::default());
+    std::fs::remove_file(&good).unwrap();
+    std::fs::remove_file(&bad).unwrap();
+
+    match result {
+        Err(Error::Record(error)) => {
+            assert_eq!(error.line, 3);
+            assert_eq!(error.file.as_deref(), Some(bad.as_path()));
+        }
+        other => panic!("expected a record error, got {:?}", other),
+    }
+    assert_eq!(detector.graph.node_count(), 2);
+    assert_eq!(edges(&detector).len(), 1);
+    assert_eq!(edge(&edges(&detector), "a", "b"), 5);
+}
+
+// One valid row, a row without a target, an unparsable weight and another valid row
+const BAD_ROWS: &str = "source,target,weight\na,b,1\nlonely\nb,c,x\nc,a,2\n";
+
+fn with_bad_rows(bad_rows: BadRowPolicy) -> CsvOptions {
+    CsvOptions {
+        bad_rows,
+        ..CsvOptions::default()
+    }
+}
+
+#[test]
+fn fail_policy_reports_first_bad_row() {
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result =
+        detector.extend_from_reader(BAD_ROWS.as_bytes(), &with_bad_rows(BadRowPolicy::Fail));
+    match result {
+        Err(Error::Record(error)) => {
+            assert_eq!(error.line, 3);
+            assert_eq!(error.kind, RecordErrorKind::MissingColumn(1));
+        }
+        other => panic!("expected a record error, got {:?}", other),
+    }
+    assert_eq!(detector.graph.node_count(), 0);
+}
+
+#[test]
+fn skip_policy_loads_the_good_rows() {
+    let mut detector = CommunityDetector::<Directed>::default();
+    let bad_rows = detector
+        .extend_from_reader(BAD_ROWS.as_bytes(), &with_bad_rows(BadRowPolicy::Skip))
+        .unwrap();
+    assert!(bad_rows.is_empty());
+    let edges = edges(&detector);
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edge(&edges, "c", "a"), 2);
+}
+
+#[test]
+fn collect_policy_returns_bad_rows_in_order() {
+    let mut detector = CommunityDetector::<Directed>::default();
+    let bad_rows = detector
+        .extend_from_reader(BAD_ROWS.as_bytes(), &with_bad_rows(BadRowPolicy::Collect))
+        .unwrap();
+    assert_eq!(
+        bad_rows,
+        [
+            RecordError::new(3, RecordErrorKind::MissingColumn(1)),
+            RecordError::new(4, RecordErrorKind::InvalidWeight("x".to_string())),
+        ]
+    );
+    assert_eq!(edges(&detector).len(), 2);
+}
+
+#[test]
+fn bad_row_lines_are_exact_across_chunks() {
+    // Several megabytes, so the input is parsed in more than one chunk
+    let mut input = String::from("source,target,weight\n");
+    let mut expected = Vec::new();
+    for row in 0..200_000 {
+        let line = row + 2;
+        if row % 9_973 == 0 {
+            input.push_str(&format!("user{},user{},-{}\n", row, row + 1, row));
+            expected.push(line as u64);
+        } else {
+            input.push_str(&format!("user{},user{},{}\n", row, row + 1, row % 10));
+        }
+    }
+    assert!(input.len() > 4 << 20);
+
+    let mut detector = CommunityDetector::<Directed>::default();
+    let bad_rows = detector
+        .extend_from_reader(input.as_bytes(), &with_bad_rows(BadRowPolicy::Collect))
+        .unwrap();
+    let lines: Vec<u64> = bad_rows.iter().map(|error| error.line).collect();
+    assert_eq!(lines, expected);
+
+    let error = detector
+        .extend_from_reader(input.as_bytes(), &with_bad_rows(BadRowPolicy::Fail))
+        .unwrap_err();
+    assert!(matches!(error, Error::Record(RecordError { line: 2, .. })));
+}
+
+#[test]
+fn bad_rows_in_files_name_the_file() {
+    let path = temp_file("bad_rows.csv");
+    std::fs::write(&path, BAD_ROWS).unwrap();
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_csv(&path, &with_bad_rows(BadRowPolicy::Collect));
+    std::fs::remove_file(&path).unwrap();
+
+    let bad_rows = result.unwrap();
+    assert_eq!(bad_rows.len(), 2);
+    assert!(
+        bad_rows
+            .iter()
+            .all(|error| error.file.as_deref() == Some(path.as_path()))
+    );
+    assert_eq!(
+        bad_rows[0].to_string(),
+        format!("{}:3: missing column 1", path.display())
+    );
+}
+
+#[test]
+fn columns_are_selected_by_name() {
+    let input = "timestamp,weight,to,from\n17,3,bob,alice\n18,4,carol,bob\n";
+    let options = CsvOptions {
+        source: Column::Name("from".to_string()),
+        target: Column::Name("to".to_string()),
+        weight: Some(Column::Name("weight".to_string())),
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &options)
+        .unwrap();
+    let edges = edges(&detector);
+    assert_eq!(edges.len(), 2);
+    assert_eq!(edge(&edges, "alice", "bob"), 3);
+    assert_eq!(edge(&edges, "bob", "carol"), 4);
+}
+
+#[test]
+fn tab_separated_input_without_header_or_weight() {
+    let input = "alice\tbob\nbob\tcarol\nalice\tbob\n";
+    let options = CsvOptions {
+        delimiter: b'\t',
+        has_headers: false,
+        weight: None,
+        default_weight: 2.0,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &options)
+        .unwrap();
+    let edges = edges(&detector);
+    assert_eq!(edge(&edges, "alice", "bob"), 4);
+    assert_eq!(edge(&edges, "bob", "carol"), 2);
+}
+
+#[test]
+fn semicolon_separated_input_with_positions() {
+    let input = "weight;source;target\n5;alice;bob\n";
+    let options = CsvOptions {
+        delimiter: b';',
+        source: Column::Index(1),
+        target: Column::Index(2),
+        weight: Some(Column::Index(0)),
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &options)
+        .unwrap();
+    assert_eq!(edge(&edges(&detector), "alice", "bob"), 5);
+}
+
+#[test]
+fn schema_errors() {
+    let load = |input: &str, options: CsvOptions| {
+        CommunityDetector::<Directed>::default().extend_from_reader(input.as_bytes(), &options)
+    };
+    let named = |has_headers| CsvOptions {
+        has_headers,
+        source: Column::Name("from".to_string()),
+        target: Column::Name("to".to_string()),
+        weight: None,
+        ..CsvOptions::default()
+    };
+
+    // A named column missing from the header
+    let result = load("from,recipient\na,b\n", named(true));
+    assert!(matches!(result, Err(Error::Schema(_))));
+    // Names without a header to look them up in
+    let result = load("a,b\n", named(false));
+    assert!(matches!(result, Err(Error::Schema(_))));
+    // A position past the end of the header
+    let options = CsvOptions {
+        weight: Some(Column::Index(5)),
+        ..CsvOptions::default()
+    };
+    let result = load("source,target,weight\na,b,1\n", options);
+    assert!(matches!(result, Err(Error::Schema(_))));
+}
+
+// Weight of the undirected edge between `a` and `b`, whichever way it is stored
+fn undirected_edge<W: Copy>(edges: &HashMap<(String, String), W>, a: &str, b: &str) -> W {
+    let key = |a: &str, b: &str| (a.to_string(), b.to_string());
+    edges
+        .get(&key(a, b))
+        .or_else(|| edges.get(&key(b, a)))
+        .copied()
+        .unwrap()
+}
+
+#[test]
+fn undirected_mode_merges_both_directions() {
+    let path = temp_file("undirected.csv");
+    std::fs::write(
+        &path,
+        "source,target,weight\na,b,1\nb,a,2\nb,c,4\nc,b,1\na,a,3\n",
+    )
+    .unwrap();
+    let directed = CommunityDetector::from_csv(path.to_str().unwrap());
+    let undirected = UndirectedCommunityDetector::from_csv_undirected(path.to_str().unwrap());
+    let options = CsvOptions {
+        aggregation: Aggregation::Max,
+        ..CsvOptions::default()
+    };
+    let max = UndirectedCommunityDetector::from_csv_undirected_with_options(
+        path.to_str().unwrap(),
+        &options,
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(directed.unwrap().graph.edge_count(), 5);
+
+    let merged = edges(&undirected.unwrap());
+    assert_eq!(merged.len(), 3);
+    assert_eq!(undirected_edge(&merged, "a", "b"), 3);
+    assert_eq!(undirected_edge(&merged, "b", "c"), 5);
+    assert_eq!(undirected_edge(&merged, "a", "a"), 3);
+
+    let (max, _) = max.unwrap();
+    let merged = edges(&max);
+    assert_eq!(undirected_edge(&merged, "a", "b"), 2);
+    assert_eq!(undirected_edge(&merged, "b", "c"), 4);
+}
+
+#[test]
+fn undirected_mode_merges_into_existing_edges() {
+    let mut detector = UndirectedCommunityDetector::default();
+    let options = CsvOptions::default();
+    detector
+        .extend_from_reader("source,target,weight\na,b,1\n".as_bytes(), &options)
+        .unwrap();
+    detector
+        .extend_from_reader("source,target,weight\nb,a,2\n".as_bytes(), &options)
+        .unwrap();
+    assert_eq!(detector.graph.edge_count(), 1);
+    assert_eq!(undirected_edge(&edges(&detector), "a", "b"), 3);
+}
+
+#[test]
+fn undirected_file_edges_load_both_ways_into_directed_graphs() {
+    let path = temp_file("undirected.gml");
+    std::fs::write(
+        &path,
+        "graph [ node [ id 0 label \"a\" ] node [ id 1 label \"b\" ] edge [ source 0 target 1 value 2 ] ]",
+    )
+    .unwrap();
+    let directed = CommunityDetector::<Directed>::load_file(
+        path.to_str().unwrap(),
+        GraphFormat::Gml,
+        &CsvOptions::default(),
+    );
+    let undirected = UndirectedCommunityDetector::load_file(
+        path.to_str().unwrap(),
+        GraphFormat::Gml,
+        &CsvOptions::default(),
+    );
+    std::fs::remove_file(&path).unwrap();
+
+    let (directed, _) = directed.unwrap();
+    let loaded = edges(&directed);
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(edge(&loaded, "a", "b"), 2);
+    assert_eq!(edge(&loaded, "b", "a"), 2);
+
+    let (undirected, _) = undirected.unwrap();
+    assert_eq!(undirected.graph.edge_count(), 1);
+}
+
+fn load_with<W: EdgeWeight>(
+    input: &str,
+    options: &CsvOptions,
+) -> Result<CommunityDetector<Directed, W>, Error> {
+    let mut detector = CommunityDetector::<Directed, W>::default();
+    detector.extend_from_reader(input.as_bytes(), options)?;
+    Ok(detector)
+}
+
+#[test]
+fn each_aggregation_combines_repeated_pairs() {
+    let input = "source,target,weight\na,b,3\nb,c,1\na,b,8\na,b,2\nb,c,6\na,b,4\n";
+    for (aggregation, a_b, b_c) in [
+        (Aggregation::Sum, 17, 7),
+        (Aggregation::Max, 8, 6),
+        (Aggregation::Min, 2, 1),
+        (Aggregation::Mean, 4, 4),
+        (Aggregation::Count, 4, 2),
+        (Aggregation::Last, 4, 6),
+    ] {
+        let options = CsvOptions {
+            aggregation,
+            ..CsvOptions::default()
+        };
+        let detector = load_with::<u32>(input, &options).unwrap();
+        let loaded = edges(&detector);
+        assert_eq!(loaded.len(), 2, "{:?}", aggregation);
+        assert_eq!(edge(&loaded, "a", "b"), a_b, "{:?}", aggregation);
+        assert_eq!(edge(&loaded, "b", "c"), b_c, "{:?}", aggregation);
+    }
+}
+
+#[test]
+fn mean_of_float_weights_is_not_rounded() {
+    let options = CsvOptions {
+        aggregation: Aggregation::Mean,
+        weight_format: WeightFormat::Float,
+        ..CsvOptions::default()
+    };
+    let input = "source,target,weight\na,b,1\na,b,2\n";
+    let detector = load_with::<f64>(input, &options).unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), 1.5);
+
+    // 1.5 rounds to 2 for integer weights
+    let detector = load_with::<u32>(input, &options).unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), 2);
+}
+
+#[test]
+fn sum_overflow_saturates_by_default() {
+    let input = format!("source,target,weight\na,b,{}\na,b,1\nb,c,1\n", u32::MAX - 1);
+    let detector = load_with::<u32>(&input, &CsvOptions::default()).unwrap();
+    let loaded = edges(&detector);
+    assert_eq!(edge(&loaded, "a", "b"), u32::MAX);
+    assert_eq!(edge(&loaded, "b", "c"), 1);
+
+    let input = format!("{}a,b,5\n", input);
+    let detector = load_with::<u32>(&input, &CsvOptions::default()).unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), u32::MAX);
+}
+
+#[test]
+fn sum_overflow_error_reports_the_line() {
+    let options = CsvOptions {
+        overflow: OverflowPolicy::Error,
+        ..CsvOptions::default()
+    };
+    let input = format!(
+        "source,target,weight\na,b,{}\nb,c,1\na,b,1\na,b,1\n",
+        u32::MAX - 1
+    );
+    match load_with::<u32>(&input, &options) {
+        Err(Error::Record(RecordError {
+            line,
+            kind: RecordErrorKind::WeightOverflow,
+            ..
+        })) => assert_eq!(line, 5),
+        other => panic!("expected an overflow error, got {:?}", other.map(|_| ())),
+    }
+
+    // Without overflow the policy changes nothing
+    let input = format!("source,target,weight\na,b,{}\na,b,1\n", u32::MAX - 1);
+    let detector = load_with::<u32>(&input, &options).unwrap();
+    assert_eq!(edge(&edges(&detector), "a", "b"), u32::MAX);
+}
+
+#[test]
+fn overflow_against_an_existing_edge() {
+    let options = CsvOptions {
+        overflow: OverflowPolicy::Error,
+        ..CsvOptions::default()
+    };
+    let mut detector = load_with::<u32>(
+        &format!("source,target,weight\na,b,{}\n", u32::MAX),
+        &options,
+    )
+    .unwrap();
+    let result =
+        detector.extend_from_reader("source,target,weight\nc,d,1\na,b,1\n".as_bytes(), &options);
+    assert!(matches!(
+        result,
+        Err(Error::Record(RecordError {
+            line: 3,
+            kind: RecordErrorKind::WeightOverflow,
+            ..
+        }))
+    ));
+    assert_eq!(edge(&edges(&detector), "a", "b"), u32::MAX);
+    assert_eq!(detector.graph.node_count(), 2);
+}
+
+#[test]
+fn last_follows_input_order_in_both_directions() {
+    let options = CsvOptions {
+        aggregation: Aggregation::Last,
+        ..CsvOptions::default()
+    };
+    // Hash maps are seeded per run, so repeat to catch order-dependent merging
+    for _ in 0..200 {
+        for (input, expected) in [
+            ("source,target,weight\na,b,5\nb,a,3\n", 3),
+            ("source,target,weight\nb,a,5\na,b,3\n", 3),
+            ("source,target,weight\na,b,3\nc,d,1\nb,a,5\n", 5),
+        ] {
+            let mut detector = UndirectedCommunityDetector::default();
+            detector
+                .extend_from_reader(input.as_bytes(), &options)
+                .unwrap();
+            assert_eq!(
+                undirected_edge(&edges(&detector), "a", "b"),
+                expected,
+                "{}",
+                input
+            );
+        }
+    }
+}
+
+#[test]
+fn self_loops_are_kept_or_dropped() {
+    let input = "source,target,weight\na,a,2\na,b,1\nb,b,4\nc,c,1\nb,b,1\n";
+    let mut kept = CommunityDetector::<Directed>::default();
+    kept.extend_from_reader(input.as_bytes(), &CsvOptions::default())
+        .unwrap();
+    let loaded = edges(&kept);
+    assert_eq!(loaded.len(), 4);
+    assert_eq!(edge(&loaded, "a", "a"), 2);
+    assert_eq!(edge(&loaded, "b", "b"), 5);
+    assert_eq!(edge(&loaded, "c", "c"), 1);
+
+    let options = CsvOptions {
+        self_loops: SelfLoopPolicy::Drop,
+        ..CsvOptions::default()
+    };
+    let mut dropped = CommunityDetector::<Directed>::default();
+    dropped
+        .extend_from_reader(input.as_bytes(), &options)
+        .unwrap();
+    let loaded = edges(&dropped);
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(edge(&loaded, "a", "b"), 1);
+    // Users that only interact with themselves leave no trace
+    assert_eq!(dropped.graph.node_count(), 2);
+}
+
+#[test]
+fn self_loops_are_dropped_from_other_formats() {
+    let path = temp_file("self_loops.txt");
+    std::fs::write(&path, "a a 2\na b 1\nb b\n").unwrap();
+    let options = CsvOptions {
+        self_loops: SelfLoopPolicy::Drop,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    let result = detector.extend_from_file(&path, GraphFormat::EdgeList, &options);
+    std::fs::remove_file(&path).unwrap();
+
+    result.unwrap();
+    let loaded = edges(&detector);
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(edge(&loaded, "a", "b"), 1);
+}
+
+#[test]
+fn glob_appends_matching_files_in_name_order() {
+    let directory = temp_file("glob");
+    std::fs::create_dir(&directory).unwrap();
+    let write = |name: &str, rows: &str| {
+        let contents = format!("source,target,weight\n{}", rows);
+        std::fs::write(directory.join(name), contents).unwrap();
+    };
+    write("day-2.csv", "a,b,7\nc,d,1\n");
+    write("day-1.csv", "a,b,2\n");
+    write("day-10.csv", "x,y,1\n");
+    write("notes.txt", "not,a,row\n");
+
+    let last = CsvOptions {
+        aggregation: Aggregation::Last,
+        ..CsvOptions::default()
+    };
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader("source,target,weight\na,b,1\nb,e,4\n".as_bytes(), &last)
+        .unwrap();
+    let pattern = directory.join("day-?.csv");
+    let result = detector.extend_from_glob(pattern.to_str().unwrap(), &last);
+
+    let literal = directory.join("day-10.csv");
+    let mut single = CommunityDetector::<Directed>::default();
+    let literal_result = single.extend_from_glob(literal.to_str().unwrap(), &last);
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    result.unwrap();
+    let loaded = edges(&detector);
+    assert_eq!(loaded.len(), 3);
+    // day-2.csv comes after day-1.csv and after what was already loaded
+    assert_eq!(edge(&loaded, "a", "b"), 7);
+    assert_eq!(edge(&loaded, "b", "e"), 4);
+    assert_eq!(edge(&loaded, "c", "d"), 1);
+
+    literal_result.unwrap();
+    assert_eq!(edge(&edges(&single), "x", "y"), 1);
+}
+
+#[test]
+fn glob_without_matches_is_an_error() {
+    let directory = temp_file("empty_glob");
+    std::fs::create_dir(&directory).unwrap();
+    std::fs::write(directory.join("day-1.txt"), "source,target,weight\n").unwrap();
+
+    let mut detector = CommunityDetector::<Directed>::default();
+    let pattern = directory.join("*.csv");
+    let result = detector.extend_from_glob(pattern.to_str().unwrap(), &CsvOptions::default());
+    let missing = directory.join("day-1.csv");
+    let missing_result =
+        detector.extend_from_glob(missing.to_str().unwrap(), &CsvOptions::default());
+    std::fs::remove_dir_all(&directory).unwrap();
+
+    match result {
+        Err(Error::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("expected no matches, got {:?}", other),
+    }
+    assert!(matches!(missing_result, Err(Error::Io(_))));
+
+    let pattern = directory.join("*").join("day-1.csv");
+    let result = detector.extend_from_glob(pattern.to_str().unwrap(), &CsvOptions::default());
+    match result {
+        Err(Error::Io(error)) => assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput),
+        other => panic!("expected a rejected pattern, got {:?}", other),
+    }
+}
@@ -0,0 +1,110 @@
+use community_detection::{CommunityDetector, canonical_labels, match_labels};
+use petgraph::Directed;
+use std::collections::HashMap;
+
+fn partition(assignments: &[(&str, usize)]) -> HashMap<String, usize> {
+    assignments
+        .iter()
+        .map(|&(user, id)| (user.to_string(), id))
+        .collect()
+}
+
+#[test]
+fn canonical_ids_follow_size_then_smallest_member() {
+    let labels = partition(&[
+        ("e", 4),
+        ("d", 4),
+        ("a", 9),
+        ("z", 9),
+        ("b", 1),
+        ("c", 1),
+        ("f", 1),
+        ("g", 2),
+    ]);
+    let expected = partition(&[
+        ("b", 0),
+        ("c", 0),
+        ("f", 0),
+        ("a", 1),
+        ("z", 1),
+        ("d", 2),
+        ("e", 2),
+        ("g", 3),
+    ]);
+    assert_eq!(canonical_labels(&labels), expected);
+}
+
+#[test]
+fn canonical_ids_ignore_the_input_ids() {
+    let labels = partition(&[("a", 0), ("b", 0), ("c", 1), ("d", 2), ("e", 2)]);
+    let renamed = partition(&[("a", 30), ("b", 30), ("c", 10), ("d", 20), ("e", 20)]);
+    assert_eq!(canonical_labels(&labels), canonical_labels(&renamed));
+    assert_eq!(
+        canonical_labels(&canonical_labels(&labels)),
+        canonical_labels(&labels)
+    );
+    assert!(canonical_labels(&HashMap::new()).is_empty());
+}
+
+#[test]
+fn matching_restores_previous_ids() {
+    let previous = partition(&[("a", 5), ("b", 5), ("c", 5), ("d", 2), ("e", 2)]);
+    let labels = partition(&[("a", 0), ("b", 0), ("c", 0), ("d", 1), ("e", 1)]);
+    assert_eq!(match_labels(&labels, &previous), previous);
+}
+
+#[test]
+fn matching_is_greedy_by_overlap() {
+    let previous = partition(&[("a", 7), ("b", 7), ("c", 7), ("d", 7), ("e", 3), ("f", 3)]);
+    // {c,d,e} and {a,b} both share two users with 7. The tie goes to
+    // {c,d,e}, which is first in canonical order, so {a,b} gets the next free
+    // id. {f,g} keeps 3 through its one shared user, and communities of new
+    // users get new ids in canonical order.
+    let labels = partition(&[
+        ("a", 0),
+        ("b", 0),
+        ("c", 1),
+        ("d", 1),
+        ("e", 1),
+        ("f", 2),
+        ("g", 2),
+        ("i", 3),
+        ("j", 3),
+        ("h", 4),
+    ]);
+    let expected = partition(&[
+        ("c", 7),
+        ("d", 7),
+        ("e", 7),
+        ("a", 8),
+        ("b", 8),
+        ("f", 3),
+        ("g", 3),
+        ("i", 9),
+        ("j", 9),
+        ("h", 10),
+    ]);
+    assert_eq!(match_labels(&labels, &previous), expected);
+}
+
+#[test]
+fn matching_without_previous_run_is_canonical() {
+    let labels = partition(&[("a", 3), ("b", 8), ("c", 8)]);
+    assert_eq!(
+        match_labels(&labels, &HashMap::new()),
+        canonical_labels(&labels)
+    );
+}
+
+#[test]
+fn detector_relabels_in_place() {
+    let mut detector = CommunityDetector::<Directed> {
+        labels: partition(&[("a", 4), ("b", 6), ("c", 6)]),
+        ..CommunityDetector::default()
+    };
+    detector.canonicalize_labels();
+    assert_eq!(detector.labels, partition(&[("a", 1), ("b", 0), ("c", 0)]));
+
+    detector.match_labels_to(&partition(&[("a", 2), ("b", 5)]));
+    assert_eq!(detector.labels, partition(&[("a", 2), ("b", 5), ("c", 5)]));
+}
@@ -0,0 +1,104 @@
+use community_detection::CommunityDetector;
+use petgraph::Graph;
+use petgraph::visit::EdgeRef;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, HashSet};
+
+fn planted_graph(seed: u64) -> Graph<String, u32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut graph = Graph::new();
+    let nodes: Vec<_> = (0..120)
+        .map(|i| graph.add_node(format!("user{}", i)))
+        .collect();
+
+    for a in 0..nodes.len() {
+        for b in 0..nodes.len() {
+            let probability = if a / 20 == b / 20 { 0.15 } else { 0.01 };
+            if a != b && rng.gen_bool(probability) {
+                graph.add_edge(nodes[a], nodes[b], rng.gen_range(1..=20));
+            }
+        }
+    }
+    graph
+}
+
+fn is_connected(graph: &Graph<String, u32>, members: &[String]) -> bool {
+    let members: HashSet<&str> = members.iter().map(String::as_str).collect();
+    let start = graph
+        .node_indices()
+        .find(|&node| members.contains(graph[node].as_str()))
+        .unwrap();
+
+    let mut visited = HashSet::from([start]);
+    let mut stack = vec![start];
+    while let Some(node) = stack.pop() {
+        for edge in graph
+            .edges(node)
+            .chain(graph.edges_directed(node, petgraph::Incoming))
+        {
+            let other = if edge.source() == node {
+                edge.target()
+            } else {
+                edge.source()
+            };
+            if members.contains(graph[other].as_str()) && visited.insert(other) {
+                stack.push(other);
+            }
+        }
+    }
+    visited.len() == members.len()
+}
+
+#[test]
+fn leiden_communities_are_connected() {
+    for seed in 0..10 {
+        let mut detector = CommunityDetector {
+            graph: planted_graph(seed),
+            labels: HashMap::new(),
+        };
+        detector.detect_communities_leiden();
+
+        assert_eq!(detector.labels.len(), detector.graph.node_count());
+        for members in detector.get_communities().values() {
+            assert!(
+                is_connected(&detector.graph, members),
+                "seed {}: {:?}",
+                seed,
+                members
+            );
+        }
+    }
+}
+
+#[test]
+fn leiden_modularity_keeps_up_with_louvain() {
+    // Both are greedy heuristics, so either can win on a single graph, but
+    // refinement should not cost modularity overall.
+    let (mut leiden_total, mut louvain_total) = (0.0, 0.0);
+    for seed in 0..10 {
+        let mut detector = CommunityDetector {
+            graph: planted_graph(seed),
+            labels: HashMap::new(),
+        };
+        detector.detect_communities_louvain();
+        let louvain = detector.quality().modularity;
+        detector.detect_communities_leiden();
+        let leiden = detector.quality().modularity;
+        assert!(
+            leiden > louvain - 0.01,
+            "seed {}: Leiden {} vs Louvain {}",
+            seed,
+            leiden,
+            louvain
+        );
+        leiden_total += leiden;
+        louvain_total += louvain;
+    }
+    assert!(
+        leiden_total >= louvain_total,
+        "{} vs {}",
+        leiden_total,
+        louvain_total
+    );
+}
@@ -0,0 +1,85 @@
+use community_detection::{CommunityDetector, partition_quality};
+use petgraph::Directed;
+use std::collections::HashMap;
+
+// Two triangles `a,b,c` and `d,e,f` joined by the edge `c,d`, all of weight 1
+const TRIANGLES: &str = "source,target,weight\na,b,1\nb,c,1\nc,a,1\nc,d,1\nd,e,1\ne,f,1\nf,d,1\n";
+
+fn detector(input: &str) -> CommunityDetector {
+    let mut detector = CommunityDetector::<Directed>::default();
+    detector
+        .extend_from_reader(input.as_bytes(), &Default::default())
+        .unwrap();
+    detector
+}
+
+fn labels(assignments: &[(&str, usize)]) -> HashMap<String, usize> {
+    assignments
+        .iter()
+        .map(|&(user, id)| (user.to_string(), id))
+        .collect()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!(
+        (actual - expected).abs() < 1e-12,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn two_triangles() {
+    let detector = detector(TRIANGLES);
+    let labels = labels(&[("a", 0), ("b", 0), ("c", 0), ("d", 1), ("e", 1), ("f", 1)]);
+    let quality = partition_quality(&detector.graph, &labels);
+
+    // Each triangle holds 3 of the 7 edges and half of the degree:
+    // Q = 2 * (3/7 - (7/14)^2) = 5/14
+    assert_close(quality.modularity, 5.0 / 14.0);
+    assert_close(quality.coverage, 6.0 / 7.0);
+    // 6 linked pairs inside triangles and 8 of the 9 pairs across them
+    assert_close(quality.performance, 14.0 / 15.0);
+    assert_close(quality.intra_weight, 6.0);
+    assert_close(quality.inter_weight, 1.0);
+    assert_close(quality.intra_inter_ratio, 6.0);
+
+    assert_eq!(quality.communities.len(), 2);
+    for community in &quality.communities {
+        assert_eq!(community.size, 3);
+        assert_close(community.internal_weight, 3.0);
+        assert_close(community.boundary_weight, 1.0);
+        assert_close(community.volume, 7.0);
+        assert_close(community.conductance, 1.0 / 7.0);
+    }
+}
+
+#[test]
+fn weights_and_both_directions_count() {
+    // `b,a` adds to `a,b`, and the bridge is heavy: m = 2+1+1+4+1+1+1 = 11
+    let input = "source,target,weight\na,b,1\nb,a,1\nb,c,1\nc,a,1\nc,d,4\nd,e,1\ne,f,1\nf,d,1\n";
+    let detector = detector(input);
+    let labels = labels(&[("a", 0), ("b", 0), ("c", 0), ("d", 1), ("e", 1), ("f", 1)]);
+    let quality = partition_quality(&detector.graph, &labels);
+
+    // Volumes are 2*4 + 4 = 12 and 2*3 + 4 = 10 out of 22
+    let expected = 4.0 / 11.0 - (12.0f64 / 22.0).powi(2) + 3.0 / 11.0 - (10.0f64 / 22.0).powi(2);
+    assert_close(quality.modularity, expected);
+    assert_close(quality.communities[0].conductance, 4.0 / 10.0);
+}
+
+#[test]
+fn single_community_and_singletons() {
+    let detector = detector(TRIANGLES);
+    let everyone = labels(&[("a", 0), ("b", 0), ("c", 0), ("d", 0), ("e", 0), ("f", 0)]);
+    let quality = partition_quality(&detector.graph, &everyone);
+    assert_close(quality.modularity, 0.0);
+    assert_close(quality.coverage, 1.0);
+    assert!(quality.intra_inter_ratio.is_infinite());
+
+    // Unlabelled users are singletons: Q = -sum((d/14)^2) = -(4*4 + 2*9)/196
+    let quality = partition_quality(&detector.graph, &HashMap::new());
+    assert_close(quality.modularity, -34.0 / 196.0);
+    assert_eq!(quality.communities.len(), 6);
+}
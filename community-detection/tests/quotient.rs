@@ -0,0 +1,163 @@
+use community_detection::{CommunityDetector, CommunityNode, quotient_graph};
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, EdgeType, Graph, Undirected};
+use std::collections::HashMap;
+
+mod common;
+use common::temp_file;
+
+fn detector<Ty: EdgeType + Sync>(rows: &str) -> CommunityDetector<Ty> {
+    let mut detector = CommunityDetector::<Ty>::default();
+    let input = format!("source,target,weight\n{}", rows);
+    detector
+        .extend_from_reader(input.as_bytes(), &Default::default())
+        .unwrap();
+    // e stays unlabelled
+    detector.labels = [("a", 8), ("b", 8), ("c", 3), ("d", 3), ("f", 5)]
+        .into_iter()
+        .map(|(user, id)| (user.to_string(), id))
+        .collect();
+    detector
+}
+
+const ROWS: &str = "a,b,3\nb,a,1\na,c,2\nc,d,4\nd,c,1\nc,c,6\nb,d,5\nd,a,2\ne,a,7\nf,a,1\n";
+
+// Edges between community ids; undirected edges are keyed smallest id first
+fn community_edges<Ty: EdgeType>(
+    quotient: &Graph<CommunityNode, u32, Ty>,
+) -> HashMap<(usize, usize), u32> {
+    let edges: HashMap<(usize, usize), u32> = quotient
+        .edge_references()
+        .map(|edge| {
+            let a = quotient[edge.source()].community_id;
+            let b = quotient[edge.target()].community_id;
+            let key = if Ty::is_directed() {
+                (a, b)
+            } else {
+                (a.min(b), a.max(b))
+            };
+            (key, *edge.weight())
+        })
+        .collect();
+    assert_eq!(edges.len(), quotient.edge_count());
+    edges
+}
+
+#[test]
+fn nodes_are_communities_in_id_order() {
+    let detector = detector::<Directed>(ROWS);
+    let quotient = detector.quotient_graph();
+    let nodes: Vec<CommunityNode> = quotient.node_weights().copied().collect();
+    assert_eq!(
+        nodes,
+        [
+            CommunityNode {
+                community_id: 3,
+                size: 2
+            },
+            CommunityNode {
+                community_id: 5,
+                size: 1
+            },
+            CommunityNode {
+                community_id: 8,
+                size: 2
+            },
+        ]
+    );
+    assert_eq!(nodes[0].to_string(), "community 3 (2 users)");
+    assert_eq!(nodes[1].to_string(), "community 5 (1 user)");
+}
+
+#[test]
+fn directed_weights_are_summed_per_direction() {
+    let detector = detector::<Directed>(ROWS);
+    let quotient = quotient_graph(&detector.graph, &detector.labels);
+    let expected = HashMap::from([
+        ((8, 8), 4),
+        ((8, 3), 7),
+        ((3, 8), 2),
+        ((3, 3), 11),
+        ((5, 8), 1),
+    ]);
+    assert_eq!(community_edges(&quotient), expected);
+}
+
+#[test]
+fn undirected_weights_merge_both_directions() {
+    let detector = detector::<Undirected>(ROWS);
+    let quotient = detector.quotient_graph();
+    let expected = HashMap::from([((8, 8), 4), ((3, 8), 9), ((3, 3), 11), ((5, 8), 1)]);
+    assert_eq!(community_edges(&quotient), expected);
+}
+
+#[test]
+fn summed_weights_saturate() {
+    let rows = format!("a,c,{}\nb,d,1\nc,d,2\n", u32::MAX);
+    let detector = detector::<Directed>(&rows);
+    let quotient = detector.quotient_graph();
+    let edges = community_edges(&quotient);
+    assert_eq!(edges[&(8, 3)], u32::MAX);
+    assert_eq!(edges[&(3, 3)], 2);
+}
+
+#[test]
+fn empty_labels_give_an_empty_graph() {
+    let mut detector = detector::<Directed>(ROWS);
+    detector.labels.clear();
+    let quotient = detector.quotient_graph();
+    assert_eq!(quotient.node_count(), 0);
+    assert_eq!(quotient.edge_count(), 0);
+}
+
+#[test]
+fn quotient_dot_labels_communities_and_weights() {
+    let detector = detector::<Directed>(ROWS);
+    let quotient_path = temp_file("quotient.dot");
+    let graph_path = temp_file("graph.dot");
+    detector
+        .save_quotient_graph_to_dot(quotient_path.to_str().unwrap())
+        .unwrap();
+    detector
+        .save_graph_to_dot(graph_path.to_str().unwrap())
+        .unwrap();
+    let quotient = std::fs::read_to_string(&quotient_path).unwrap();
+    let graph = std::fs::read_to_string(&graph_path).unwrap();
+    std::fs::remove_file(&quotient_path).unwrap();
+    std::fs::remove_file(&graph_path).unwrap();
+
+    let fill = |line: &str| line.split("fillcolor=").nth(1).unwrap().to_string();
+    let node_lines: Vec<&str> = quotient
+        .lines()
+        .filter(|line| line.contains("fillcolor"))
+        .collect();
+    assert_eq!(node_lines.len(), 3);
+    for (community, user) in [(3, "c"), (5, "f"), (8, "a")] {
+        let node = node_lines
+            .iter()
+            .find(|line| line.contains(&format!("community {} (", community)))
+            .unwrap();
+        // Communities keep the color of their members in the full graph
+        let member = graph
+            .lines()
+            .find(|line| line.contains(&format!("\"{}\"", user)))
+            .unwrap();
+        assert_eq!(fill(node), fill(member), "community {}", community);
+    }
+    assert!(quotient.contains("label=\"community 5 (1 user)\""));
+
+    let mut edge_labels: Vec<&str> = quotient
+        .lines()
+        .filter(|line| line.contains("->"))
+        .map(|line| {
+            line.split("label=\"")
+                .nth(1)
+                .unwrap()
+                .split('"')
+                .next()
+                .unwrap()
+        })
+        .collect();
+    edge_labels.sort();
+    assert_eq!(edge_labels, ["1", "11", "2", "4", "7"]);
+}
@@ -0,0 +1,101 @@
+use community_detection::{
+    Column, CommunityDetector, CsvOptions, Error, INTERACTION_HEADER,
+    generate_interaction_csv_seeded,
+};
+use petgraph::visit::EdgeRef;
+use std::collections::{HashMap, HashSet};
+
+mod common;
+use common::temp_file;
+
+#[test]
+fn every_generated_interaction_is_loaded() {
+    let path = temp_file("round_trip.csv");
+    let filename = path.to_str().unwrap();
+    generate_interaction_csv_seeded(200, 5000, filename, 7).unwrap();
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let mut lines = contents.lines();
+    assert_eq!(lines.next(), Some(INTERACTION_HEADER));
+
+    let mut expected: HashMap<(String, String), u32> = HashMap::new();
+    let mut rows = 0;
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        let weight: u32 = fields[2].parse().unwrap();
+        *expected
+            .entry((fields[0].to_string(), fields[1].to_string()))
+            .or_default() += weight;
+        rows += 1;
+    }
+    assert_eq!(rows, 5000);
+
+    let detector = CommunityDetector::from_csv(filename).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let graph = &detector.graph;
+
+    let loaded: HashMap<(String, String), u32> = graph
+        .edge_references()
+        .map(|edge| {
+            let key = (graph[edge.source()].clone(), graph[edge.target()].clone());
+            (key, *edge.weight())
+        })
+        .collect();
+    assert_eq!(loaded.len(), graph.edge_count());
+    assert_eq!(loaded, expected);
+
+    let users: HashSet<&String> = expected.keys().flat_map(|(a, b)| [a, b]).collect();
+    assert_eq!(graph.node_count(), users.len());
+}
+
+#[test]
+fn input_without_header_is_rejected() {
+    let path = temp_file("no_header.csv");
+    std::fs::write(&path, "alice,bob,3\nbob,carol,1\n").unwrap();
+
+    let result = CommunityDetector::from_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(result, Err(Error::Schema(_))));
+}
+
+#[test]
+fn header_must_match_the_default_columns() {
+    for header in ["target,source,weight", "from,to,count", "source,target"] {
+        let path = temp_file("other_header.csv");
+        std::fs::write(&path, format!("{}\nalice,bob,3\n", header)).unwrap();
+        let result = CommunityDetector::from_csv(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(Error::Schema(_))), "{}", header);
+    }
+
+    let path = temp_file("padded_header.csv");
+    std::fs::write(
+        &path,
+        " source , target , weight ,timestamp\nalice,bob,3,17\n",
+    )
+    .unwrap();
+    let result = CommunityDetector::from_csv(path.to_str().unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(result.unwrap().graph.edge_count(), 1);
+}
+
+#[test]
+fn other_headers_load_with_named_columns() {
+    let path = temp_file("named_header.csv");
+    std::fs::write(&path, "to,from,count\nbob,alice,3\n").unwrap();
+    let options = CsvOptions {
+        source: Column::Name("from".to_string()),
+        target: Column::Name("to".to_string()),
+        weight: Some(Column::Name("count".to_string())),
+        ..CsvOptions::default()
+    };
+    let result = CommunityDetector::from_csv_with_options(path.to_str().unwrap(), &options);
+    std::fs::remove_file(&path).unwrap();
+
+    let (detector, _) = result.unwrap();
+    let graph = &detector.graph;
+    let edge = graph.edge_references().next().unwrap();
+    assert_eq!(graph[edge.source()], "alice");
+    assert_eq!(graph[edge.target()], "bob");
+    assert_eq!(*edge.weight(), 3);
+}
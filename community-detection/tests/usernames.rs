@@ -0,0 +1,81 @@
+use community_detection::{NamespaceExhausted, UsernameGenerator};
+use std::collections::HashSet;
+
+fn assert_unique(names: &[String]) {
+    let distinct: HashSet<&String> = names.iter().collect();
+    assert_eq!(distinct.len(), names.len());
+}
+
+#[test]
+fn batches_have_exactly_the_requested_size() {
+    let generator = UsernameGenerator::new();
+    let mut all = Vec::new();
+    for count in [0, 1, 1000, 20_000] {
+        let names = generator.generate_unique_batch(count).unwrap();
+        assert_eq!(names.len(), count);
+        all.extend(names);
+    }
+    assert_unique(&all);
+}
+
+#[test]
+fn whole_namespace_can_be_drawn() {
+    let generator = UsernameGenerator::with_vocabulary(["a", "b"], ["x", "y", "z"], 1..=3);
+    assert_eq!(generator.capacity(), 18);
+
+    let mut names = generator.generate_unique_batch(10).unwrap();
+    names.extend(generator.generate_unique_batch(8).unwrap());
+    assert_unique(&names);
+    assert_eq!(names.len(), 18);
+
+    assert_eq!(
+        generator.generate_unique_batch(1),
+        Err(NamespaceExhausted {
+            requested: 1,
+            available: 0
+        })
+    );
+}
+
+#[test]
+fn oversized_request_is_rejected() {
+    let generator = UsernameGenerator::with_vocabulary(["a"], ["x"], 1..=5);
+    assert_eq!(
+        generator.generate_unique_batch(6),
+        Err(NamespaceExhausted {
+            requested: 6,
+            available: 5
+        })
+    );
+    // The failed request used up nothing.
+    assert_eq!(generator.generate_unique_batch(5).unwrap().len(), 5);
+}
+
+#[test]
+fn names_spelled_twice_count_once() {
+    // `a` + `b1` and `ab` + `1` are the same name, so only 3 of the 4 exist.
+    let generator = UsernameGenerator::with_vocabulary(["a", "ab"], ["b", ""], 1..=1);
+    assert_eq!(generator.capacity(), 4);
+    assert_eq!(
+        generator.generate_unique_batch(4),
+        Err(NamespaceExhausted {
+            requested: 4,
+            available: 3
+        })
+    );
+
+    let names = generator.generate_unique_batch(3).unwrap();
+    assert_unique(&names);
+    assert_eq!(names.len(), 3);
+}
+
+#[test]
+fn empty_vocabulary_has_no_names() {
+    let generator = UsernameGenerator::with_vocabulary(Vec::<String>::new(), Vec::new(), 1..=9);
+    assert_eq!(generator.capacity(), 0);
+    assert!(generator.generate_unique_batch(1).is_err());
+    assert_eq!(
+        generator.generate_unique_batch(0).unwrap(),
+        Vec::<String>::new()
+    );
+}